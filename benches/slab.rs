@@ -1,7 +1,7 @@
 use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
 use either::Either;
 use miniptr::{
-    pool::slab::KeySlabPool,
+    pool::slab::{BitmapSlabPool, KeySlabPool, MinSlabPool},
     pool::{slab::SlabPool, *},
     slot::DefaultSlot,
 };
@@ -73,6 +73,36 @@ fn pool_benchmark(c: &mut Criterion) {
             })
         });
 
+        group.bench_with_input(BenchmarkId::new("default-min", size), &size, |b, _| {
+            b.iter(|| {
+                let mut pool: MinSlabPool<DefaultSlot<usize>> = MinSlabPool::new();
+                let mut keys = vec![0; trace.len()];
+                for &event in trace.iter() {
+                    if event >= 0 {
+                        keys[event as usize] = black_box(pool.insert(event as usize));
+                    } else {
+                        black_box(pool.remove(keys[-(event + 1) as usize]));
+                    }
+                    black_box(&mut pool);
+                }
+            })
+        });
+
+        group.bench_with_input(BenchmarkId::new("default-bitmap", size), &size, |b, _| {
+            b.iter(|| {
+                let mut pool: BitmapSlabPool<DefaultSlot<usize>> = BitmapSlabPool::new();
+                let mut keys = vec![0; trace.len()];
+                for &event in trace.iter() {
+                    if event >= 0 {
+                        keys[event as usize] = black_box(pool.insert(event as usize));
+                    } else {
+                        black_box(pool.remove(keys[-(event + 1) as usize]));
+                    }
+                    black_box(&mut pool);
+                }
+            })
+        });
+
         group.bench_with_input(
             BenchmarkId::new("vec-push-overwrite", size),
             &size,
@@ -2,13 +2,22 @@
 Lists backed by a pool
 */
 
-use std::{fmt::Debug, hash::Hash, marker::PhantomData};
+use std::{
+    cmp::Ordering,
+    fmt::{Debug, Display},
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+    ops::{Deref, DerefMut, Index, IndexMut},
+    slice::SliceIndex,
+};
 
 use bytemuck::TransparentWrapper;
 
 use crate::pool::{
     container::{
         array::{ArrayMutPool, ArrayRefPool, InsertFromSlice, SliceMutPool, SliceRefPool},
+        deque::DequePool,
+        iter::{IterMutPool, IterPool},
         stack::StackPool,
         ContainerPool, InsertEmpty, InsertWithCapacity, IsEmptyPool, LenPool,
     },
@@ -180,6 +189,215 @@ where
         Some(result)
     }
 
+    /// Push an element to the front of this list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn push_front(&mut self, item: T, pool: &mut P)
+    where
+        P: DequePool<K>,
+    {
+        self.ix = pool.into_pushed_front(self.ix, item);
+    }
+
+    /// Pop an element from the front of this list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn pop_front(&mut self, pool: &mut P) -> Option<T>
+    where
+        P: DequePool<K>,
+    {
+        let (ix, result) = pool.into_popped_front(self.ix)?;
+        self.ix = ix;
+        Some(result)
+    }
+
+    /// Push an element to the back of this list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn push_back(&mut self, item: T, pool: &mut P)
+    where
+        P: DequePool<K>,
+    {
+        self.ix = pool.into_pushed_back(self.ix, item);
+    }
+
+    /// Pop an element from the back of this list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn pop_back(&mut self, pool: &mut P) -> Option<T>
+    where
+        P: DequePool<K>,
+    {
+        let (ix, result) = pool.into_popped_back(self.ix)?;
+        self.ix = ix;
+        Some(result)
+    }
+
+    /// Insert an element at position `index`, shifting all elements after it to the right
+    ///
+    /// Panics if `index > len`
+    #[cfg_attr(not(tarpaulin), inline)]
+    #[track_caller]
+    pub fn insert(&mut self, index: usize, item: T, pool: &mut P)
+    where
+        P: StackPool<K> + SliceMutPool<K>,
+    {
+        let len = pool.key_len(self.ix);
+        assert!(
+            index <= len,
+            "insertion index (is {index}) should be <= len (is {len})"
+        );
+        self.push(item, pool);
+        pool.slice_at_mut(self.ix)[index..].rotate_right(1);
+    }
+
+    /// Remove and return the element at position `index`, shifting all elements after it to the left
+    ///
+    /// Panics if `index` is out of bounds
+    #[cfg_attr(not(tarpaulin), inline)]
+    #[track_caller]
+    pub fn remove(&mut self, index: usize, pool: &mut P) -> T
+    where
+        P: StackPool<K> + SliceMutPool<K>,
+    {
+        let len = pool.key_len(self.ix);
+        assert!(
+            index < len,
+            "removal index (is {index}) should be < len (is {len})"
+        );
+        pool.slice_at_mut(self.ix)[index..].rotate_left(1);
+        self.pop(pool).expect("list is non-empty")
+    }
+
+    /// Remove and return the element at position `index`, replacing it with the last element of this list
+    ///
+    /// Does not preserve ordering, but takes constant time. Panics if `index` is out of bounds
+    #[cfg_attr(not(tarpaulin), inline)]
+    #[track_caller]
+    pub fn swap_remove(&mut self, index: usize, pool: &mut P) -> T
+    where
+        P: StackPool<K> + SliceMutPool<K>,
+    {
+        let len = pool.key_len(self.ix);
+        assert!(
+            index < len,
+            "swap_remove index (is {index}) should be < len (is {len})"
+        );
+        pool.slice_at_mut(self.ix).swap(index, len - 1);
+        self.pop(pool).expect("list is non-empty")
+    }
+
+    /// Shorten this list to `len` elements, dropping the rest
+    ///
+    /// Has no effect if `len` is greater than or equal to the length of this list
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn truncate(&mut self, len: usize, pool: &mut P)
+    where
+        P: StackPool<K> + LenPool<K>,
+    {
+        while pool.key_len(self.ix) > len {
+            self.pop(pool);
+        }
+    }
+
+    /// Remove all elements from this list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn clear(&mut self, pool: &mut P)
+    where
+        P: StackPool<K>,
+    {
+        self.ix = pool.cleared_key(self.ix);
+    }
+
+    /// Push every element of an iterator to this list
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn extend(&mut self, iter: impl IntoIterator<Item = T>, pool: &mut P)
+    where
+        P: StackPool<K>,
+    {
+        for item in iter {
+            self.push(item, pool);
+        }
+    }
+
+    /// Retain only the elements for which `f` returns `true`, preserving their order
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn retain(&mut self, mut f: impl FnMut(&T) -> bool, pool: &mut P)
+    where
+        P: StackPool<K> + SliceMutPool<K>,
+    {
+        let slice = pool.slice_at_mut(self.ix);
+        let mut kept = 0;
+        for ix in 0..slice.len() {
+            if f(&slice[ix]) {
+                slice.swap(kept, ix);
+                kept += 1;
+            }
+        }
+        self.truncate(kept, pool);
+    }
+
+    /// Sort this list with a comparison function, preserving the order of equal elements
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn sort_by(&self, compare: impl FnMut(&T, &T) -> Ordering, pool: &mut P)
+    where
+        P: SliceMutPool<K>,
+    {
+        pool.slice_at_mut(self.ix).sort_by(compare)
+    }
+
+    /// Binary search this sorted list with a comparison function
+    ///
+    /// See [`slice::binary_search_by`] for details
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn binary_search_by(&self, f: impl FnMut(&T) -> Ordering, pool: &P) -> Result<usize, usize>
+    where
+        P: SliceRefPool<K>,
+    {
+        pool.slice_at(self.ix).binary_search_by(f)
+    }
+
+    /// Get the first element of this list, or `None` if it is empty
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn first<'a>(&self, pool: &'a P) -> Option<&'a T>
+    where
+        P: ArrayRefPool<K>,
+    {
+        pool.get_index(self.ix, 0)
+    }
+
+    /// Get the last element of this list, or `None` if it is empty
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn last<'a>(&self, pool: &'a P) -> Option<&'a T>
+    where
+        P: ArrayRefPool<K>,
+    {
+        let len = pool.key_len(self.ix);
+        pool.get_index(self.ix, len.checked_sub(1)?)
+    }
+
+    /// Get a subslice of this list, or `None` if the range is out of bounds
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get_range<'a, R>(&self, range: R, pool: &'a P) -> Option<&'a [T]>
+    where
+        P: SliceRefPool<K>,
+        R: SliceIndex<[T], Output = [T]>,
+    {
+        pool.slice_at(self.ix).get(range)
+    }
+
+    /// Get a mutable subslice of this list, or `None` if the range is out of bounds
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get_range_mut<'a, R>(&self, range: R, pool: &'a mut P) -> Option<&'a mut [T]>
+    where
+        P: SliceMutPool<K>,
+        R: SliceIndex<[T], Output = [T]>,
+    {
+        pool.slice_at_mut(self.ix).get_mut(range)
+    }
+
+    /// Delete this list, returning its storage to the pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn free(self, pool: &mut P) {
+        pool.delete(self.ix)
+    }
+
     /// Get a reference to an element in this list
     #[cfg_attr(not(tarpaulin), inline(always))]
     pub fn get<'a>(&self, ix: usize, pool: &'a P) -> Option<&'a T>
@@ -198,6 +416,99 @@ where
         pool.get_index_mut(self.ix, ix)
     }
 
+    /// Iterate over the elements of this list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn iter<'a>(&self, pool: &'a P) -> P::Iter<'a>
+    where
+        P: IterPool<K>,
+    {
+        pool.iter_at(self.ix)
+    }
+
+    /// Iterate mutably over the elements of this list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn iter_mut<'a>(&self, pool: &'a mut P) -> P::IterMut<'a>
+    where
+        P: IterMutPool<K>,
+    {
+        pool.iter_at_mut(self.ix)
+    }
+
+    /// Borrow this list together with its pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn bind<'a>(&self, pool: &'a P) -> ListRef<'a, T, K, P> {
+        ListRef { list: *self, pool }
+    }
+
+    /// Borrow this list mutably together with its pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn bind_mut<'a>(&'a mut self, pool: &'a mut P) -> ListMut<'a, T, K, P> {
+        ListMut { list: self, pool }
+    }
+
+    /// Check whether this list has the same elements as `other`, both of which live in `pool`
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn eq_in(&self, other: &Self, pool: &P) -> bool
+    where
+        P: IterPool<K>,
+        T: PartialEq,
+    {
+        self.contents(pool) == other.contents(pool)
+    }
+
+    /// Compare the elements of this list lexicographically with those of `other`, both of which live in `pool`
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn cmp_in(&self, other: &Self, pool: &P) -> Ordering
+    where
+        P: IterPool<K>,
+        T: Ord,
+    {
+        self.contents(pool).cmp(&other.contents(pool))
+    }
+
+    /// Hash the elements of this list, consistently with [`EntityList::eq_in`]
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn hash_in<H: Hasher>(&self, pool: &P, state: &mut H)
+    where
+        P: IterPool<K>,
+        T: Hash,
+    {
+        self.contents(pool).hash(state)
+    }
+
+    /// Get a view of the elements of this list which compares, hashes and formats by value
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn contents<'a>(&self, pool: &'a P) -> Contents<'a, T, K, P>
+    where
+        P: IterPool<K>,
+    {
+        Contents { list: *self, pool }
+    }
+
+    /// Get an adaptor which formats the elements of this list with [`Display`], as in `[1, 2, 3]`
+    ///
+    /// This is the same as [`EntityList::contents`], named for use in format strings
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn display<'a>(&self, pool: &'a P) -> Contents<'a, T, K, P>
+    where
+        P: IterPool<K>,
+        T: Display,
+    {
+        self.contents(pool)
+    }
+
+    /// Get an adaptor which formats the elements of this list with [`Debug`], as for a slice
+    ///
+    /// This is the same as [`EntityList::contents`], named for use in format strings
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn debug<'a>(&self, pool: &'a P) -> Contents<'a, T, K, P>
+    where
+        P: IterPool<K>,
+        T: Debug,
+    {
+        self.contents(pool)
+    }
+
     /// Get this list as a slice
     #[cfg_attr(not(tarpaulin), inline(always))]
     pub fn as_slice<'a>(&self, pool: &'a P) -> &'a [T]
@@ -217,14 +528,669 @@ where
     }
 }
 
+/// The elements of an [`EntityList`] in a given pool, created by [`EntityList::contents`]
+///
+/// Unlike an [`EntityList`], which compares and hashes by key, this compares, hashes and formats by the list's elements, and so can be used
+/// to deduplicate lists by value, for example as the key of a [`HashMap`](std::collections::HashMap).
+pub struct Contents<'a, T, K, P> {
+    list: EntityList<T, K, P>,
+    pool: &'a P,
+}
+
+impl<'a, T, K, P> Contents<'a, T, K, P>
+where
+    K: Copy,
+    P: IterPool<K, Elem = T>,
+{
+    /// Get the list whose elements these are
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn list(&self) -> EntityList<T, K, P> {
+        self.list
+    }
+
+    /// Iterate over the elements of the list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn iter(&self) -> P::Iter<'a> {
+        self.list.iter(self.pool)
+    }
+}
+
+impl<'a, T, K, P> Clone for Contents<'a, T, K, P>
+where
+    K: Copy,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, K, P> Copy for Contents<'a, T, K, P> where K: Copy {}
+
+impl<'a, T, K, P> PartialEq for Contents<'a, T, K, P>
+where
+    K: Copy,
+    P: IterPool<K, Elem = T>,
+    T: PartialEq,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn eq(&self, other: &Self) -> bool {
+        self.iter().eq(other.iter())
+    }
+}
+
+impl<'a, T, K, P> Eq for Contents<'a, T, K, P>
+where
+    K: Copy,
+    P: IterPool<K, Elem = T>,
+    T: Eq,
+{
+}
+
+impl<'a, T, K, P> PartialOrd for Contents<'a, T, K, P>
+where
+    K: Copy,
+    P: IterPool<K, Elem = T>,
+    T: PartialOrd,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<'a, T, K, P> Ord for Contents<'a, T, K, P>
+where
+    K: Copy,
+    P: IterPool<K, Elem = T>,
+    T: Ord,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<'a, T, K, P> Hash for Contents<'a, T, K, P>
+where
+    K: Copy,
+    P: IterPool<K, Elem = T>,
+    T: Hash,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let mut len = 0;
+        for elem in self.iter() {
+            elem.hash(state);
+            len += 1;
+        }
+        // As for a slice, the length is hashed so that lists which are prefixes of one another hash differently
+        state.write_usize(len);
+    }
+}
+
+impl<'a, T, K, P> Debug for Contents<'a, T, K, P>
+where
+    K: Copy,
+    P: IterPool<K, Elem = T>,
+    T: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<'a, T, K, P> Display for Contents<'a, T, K, P>
+where
+    K: Copy,
+    P: IterPool<K, Elem = T>,
+    T: Display,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "[")?;
+        for (ix, elem) in self.iter().enumerate() {
+            if ix != 0 {
+                write!(f, ", ")?;
+            }
+            Display::fmt(elem, f)?;
+        }
+        write!(f, "]")
+    }
+}
+
+/// An [`EntityList`] borrowed together with its pool, created by [`EntityList::bind`]
+pub struct ListRef<'a, T, K, P> {
+    list: EntityList<T, K, P>,
+    pool: &'a P,
+}
+
+impl<'a, T, K, P> Clone for ListRef<'a, T, K, P>
+where
+    K: Copy,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<'a, T, K, P> Copy for ListRef<'a, T, K, P> where K: Copy {}
+
+impl<'a, T, K, P> ListRef<'a, T, K, P>
+where
+    K: Copy,
+    P: ContainerPool<K, Elem = T>,
+{
+    /// Get the underlying list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn list(&self) -> EntityList<T, K, P> {
+        self.list
+    }
+
+    /// Get the pool this list lives in
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn pool(&self) -> &'a P {
+        self.pool
+    }
+
+    /// Get the length of this list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn len(&self) -> usize
+    where
+        P: LenPool<K>,
+    {
+        self.list.len(self.pool)
+    }
+
+    /// Return `true` if this list has a length of 0
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn is_empty(&self) -> bool
+    where
+        P: IsEmptyPool<K>,
+    {
+        self.list.is_empty(self.pool)
+    }
+
+    /// Get a reference to an element in this list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get(&self, ix: usize) -> Option<&'a T>
+    where
+        P: ArrayRefPool<K>,
+    {
+        self.list.get(ix, self.pool)
+    }
+
+    /// Iterate over the elements of this list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn iter(&self) -> P::Iter<'a>
+    where
+        P: IterPool<K>,
+    {
+        self.list.iter(self.pool)
+    }
+
+    /// Get a view of the elements of this list which compares, hashes and formats by value
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn contents(&self) -> Contents<'a, T, K, P>
+    where
+        P: IterPool<K>,
+    {
+        self.list.contents(self.pool)
+    }
+}
+
+impl<'a, T, K, P> Index<usize> for ListRef<'a, T, K, P>
+where
+    K: Copy,
+    P: ArrayRefPool<K, Elem = T>,
+{
+    type Output = T;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    fn index(&self, ix: usize) -> &T {
+        self.get(ix).expect("list index out of bounds")
+    }
+}
+
+impl<'a, T, K, P> Deref for ListRef<'a, T, K, P>
+where
+    K: Copy,
+    P: SliceRefPool<K, Elem = T>,
+{
+    type Target = [T];
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn deref(&self) -> &[T] {
+        self.list.as_slice(self.pool)
+    }
+}
+
+impl<'a, T, K, P> IntoIterator for ListRef<'a, T, K, P>
+where
+    K: Copy,
+    P: IterPool<K, Elem = T>,
+    T: 'a,
+{
+    type Item = &'a T;
+    type IntoIter = P::Iter<'a>;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, K, P> Debug for ListRef<'a, T, K, P>
+where
+    K: Copy,
+    P: IterPool<K, Elem = T>,
+    T: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self.contents(), f)
+    }
+}
+
+/// An [`EntityList`] borrowed mutably together with its pool, created by [`EntityList::bind_mut`]
+///
+/// Operations which move the list within the pool update the borrowed [`EntityList`] in place.
+pub struct ListMut<'a, T, K, P> {
+    list: &'a mut EntityList<T, K, P>,
+    pool: &'a mut P,
+}
+
+impl<'a, T, K, P> ListMut<'a, T, K, P>
+where
+    K: Copy,
+    P: ContainerPool<K, Elem = T>,
+{
+    /// Get the underlying list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn list(&self) -> EntityList<T, K, P> {
+        *self.list
+    }
+
+    /// Reborrow this list immutably
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn as_ref(&self) -> ListRef<'_, T, K, P> {
+        self.list.bind(self.pool)
+    }
+
+    /// Get the length of this list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn len(&self) -> usize
+    where
+        P: LenPool<K>,
+    {
+        self.list.len(self.pool)
+    }
+
+    /// Return `true` if this list has a length of 0
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn is_empty(&self) -> bool
+    where
+        P: IsEmptyPool<K>,
+    {
+        self.list.is_empty(self.pool)
+    }
+
+    /// Get a reference to an element in this list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get(&self, ix: usize) -> Option<&T>
+    where
+        P: ArrayRefPool<K>,
+    {
+        self.list.get(ix, self.pool)
+    }
+
+    /// Get a mutable reference to an element in this list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get_mut(&mut self, ix: usize) -> Option<&mut T>
+    where
+        P: ArrayMutPool<K>,
+    {
+        self.list.get_mut(ix, self.pool)
+    }
+
+    /// Iterate over the elements of this list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn iter(&self) -> P::Iter<'_>
+    where
+        P: IterPool<K>,
+    {
+        self.list.iter(self.pool)
+    }
+
+    /// Iterate mutably over the elements of this list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn iter_mut(&mut self) -> P::IterMut<'_>
+    where
+        P: IterMutPool<K>,
+    {
+        self.list.iter_mut(self.pool)
+    }
+
+    /// Push an element to this list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn push(&mut self, item: T)
+    where
+        P: StackPool<K>,
+    {
+        self.list.push(item, self.pool)
+    }
+
+    /// Pop an element from this list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn pop(&mut self) -> Option<T>
+    where
+        P: StackPool<K>,
+    {
+        self.list.pop(self.pool)
+    }
+
+    /// Insert an element at position `index`, shifting all elements after it to the right
+    ///
+    /// Panics if `index > len`
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    pub fn insert(&mut self, index: usize, item: T)
+    where
+        P: StackPool<K> + SliceMutPool<K>,
+    {
+        self.list.insert(index, item, self.pool)
+    }
+
+    /// Remove and return the element at position `index`, shifting all elements after it to the left
+    ///
+    /// Panics if `index` is out of bounds
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    pub fn remove(&mut self, index: usize) -> T
+    where
+        P: StackPool<K> + SliceMutPool<K>,
+    {
+        self.list.remove(index, self.pool)
+    }
+
+    /// Shorten this list to `len` elements, dropping the rest
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn truncate(&mut self, len: usize)
+    where
+        P: StackPool<K> + LenPool<K>,
+    {
+        self.list.truncate(len, self.pool)
+    }
+
+    /// Remove all elements from this list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn clear(&mut self)
+    where
+        P: StackPool<K>,
+    {
+        self.list.clear(self.pool)
+    }
+
+    /// Retain only the elements for which `f` returns `true`, preserving their order
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn retain(&mut self, f: impl FnMut(&T) -> bool)
+    where
+        P: StackPool<K> + SliceMutPool<K>,
+    {
+        self.list.retain(f, self.pool)
+    }
+}
+
+impl<'a, T, K, P> Index<usize> for ListMut<'a, T, K, P>
+where
+    K: Copy,
+    P: ArrayRefPool<K, Elem = T>,
+{
+    type Output = T;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    fn index(&self, ix: usize) -> &T {
+        self.get(ix).expect("list index out of bounds")
+    }
+}
+
+impl<'a, T, K, P> IndexMut<usize> for ListMut<'a, T, K, P>
+where
+    K: Copy,
+    P: ArrayRefPool<K, Elem = T> + ArrayMutPool<K>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    fn index_mut(&mut self, ix: usize) -> &mut T {
+        self.get_mut(ix).expect("list index out of bounds")
+    }
+}
+
+impl<'a, T, K, P> Deref for ListMut<'a, T, K, P>
+where
+    K: Copy,
+    P: SliceRefPool<K, Elem = T>,
+{
+    type Target = [T];
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn deref(&self) -> &[T] {
+        self.list.as_slice(self.pool)
+    }
+}
+
+impl<'a, T, K, P> DerefMut for ListMut<'a, T, K, P>
+where
+    K: Copy,
+    P: SliceRefPool<K, Elem = T> + SliceMutPool<K>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn deref_mut(&mut self) -> &mut [T] {
+        self.list.as_slice_mut(self.pool)
+    }
+}
+
+impl<'a, T, K, P> IntoIterator for ListMut<'a, T, K, P>
+where
+    K: Copy,
+    P: IterMutPool<K, Elem = T>,
+{
+    type Item = &'a mut T;
+    type IntoIter = P::IterMut<'a>;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn into_iter(self) -> Self::IntoIter {
+        self.list.iter_mut(self.pool)
+    }
+}
+
+impl<'a, 'b, T, K, P> IntoIterator for &'b ListMut<'a, T, K, P>
+where
+    K: Copy,
+    P: IterPool<K, Elem = T>,
+{
+    type Item = &'b T;
+    type IntoIter = P::Iter<'b>;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, K, P> Extend<T> for ListMut<'a, T, K, P>
+where
+    K: Copy,
+    P: StackPool<K, Elem = T>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.list.extend(iter, self.pool)
+    }
+}
+
+impl<'a, T, K, P> Debug for ListMut<'a, T, K, P>
+where
+    K: Copy,
+    P: IterPool<K, Elem = T>,
+    T: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self.as_ref(), f)
+    }
+}
+
 #[cfg(test)]
 mod test {
-    use std::cmp::Ordering;
+    use std::{
+        cmp::Ordering,
+        collections::{hash_map::DefaultHasher, HashMap},
+        hash::Hasher,
+    };
 
-    use crate::{pool::slab::SlabPool, slot::DefaultSlot};
+    use crate::{
+        pool::{
+            container::{array::VecPool, InsertEmpty},
+            list::ListPool,
+            slab::SlabPool,
+        },
+        slot::DefaultSlot,
+    };
 
     use super::EntityList;
 
+    fn vec_like_test<K, P>(mut pool: P)
+    where
+        K: Copy,
+        P: VecPool<K, Elem = u32> + InsertEmpty<K>,
+    {
+        let mut v = EntityList::new(&mut pool);
+        assert_eq!(v.first(&pool), None);
+        assert_eq!(v.last(&pool), None);
+        v.extend([5, 1, 4], &mut pool);
+        v.insert(0, 2, &mut pool);
+        v.insert(4, 3, &mut pool);
+        v.insert(2, 0, &mut pool);
+        assert_eq!(v.as_slice(&pool), [2, 5, 0, 1, 4, 3]);
+        assert_eq!(v.first(&pool), Some(&2));
+        assert_eq!(v.last(&pool), Some(&3));
+        assert_eq!(v.remove(1, &mut pool), 5);
+        assert_eq!(v.swap_remove(0, &mut pool), 2);
+        assert_eq!(v.as_slice(&pool), [3, 0, 1, 4]);
+        v.sort_by(|l, r| l.cmp(r), &mut pool);
+        assert_eq!(v.as_slice(&pool), [0, 1, 3, 4]);
+        assert_eq!(v.binary_search_by(|x| x.cmp(&3), &pool), Ok(2));
+        assert_eq!(v.binary_search_by(|x| x.cmp(&2), &pool), Err(2));
+        assert_eq!(v.get_range(1..3, &pool), Some(&[1, 3][..]));
+        assert_eq!(v.get_range(3..5, &pool), None);
+        v.get_range_mut(..2, &mut pool).unwrap().reverse();
+        assert_eq!(v.as_slice(&pool), [1, 0, 3, 4]);
+        v.extend(5..10, &mut pool);
+        v.retain(|x| x % 3 != 0, &mut pool);
+        assert_eq!(v.as_slice(&pool), [1, 4, 5, 7, 8]);
+        v.truncate(7, &mut pool);
+        assert_eq!(v.len(&pool), 5);
+        v.truncate(2, &mut pool);
+        assert_eq!(v.as_slice(&pool), [1, 4]);
+        v.clear(&mut pool);
+        assert!(v.is_empty(&pool));
+        v.push(1, &mut pool);
+        v.free(&mut pool);
+    }
+
+    #[test]
+    fn vec_like_entity_list() {
+        vec_like_test::<u32, _>(SlabPool::<DefaultSlot<Vec<u32>>, u32>::new());
+        vec_like_test::<u32, _>(ListPool::<u32>::new());
+    }
+
+    #[test]
+    fn entity_list_contents() {
+        let mut pool: SlabPool<DefaultSlot<Vec<u32>>, u32> = SlabPool::new();
+        let a = EntityList::new_from(vec![1, 2, 3], &mut pool);
+        let b = EntityList::new_from(vec![1, 2, 3], &mut pool);
+        let c = EntityList::new_from(vec![1, 2], &mut pool);
+        assert_ne!(a, b);
+        assert!(a.eq_in(&b, &pool));
+        assert!(!a.eq_in(&c, &pool));
+        assert_eq!(a.cmp_in(&b, &pool), Ordering::Equal);
+        assert_eq!(c.cmp_in(&a, &pool), Ordering::Less);
+        assert_eq!(format!("{}", a.display(&pool)), "[1, 2, 3]");
+        assert_eq!(format!("{:?}", c.debug(&pool)), "[1, 2]");
+        assert_eq!(format!("{:02}", c.display(&pool)), "[01, 02]");
+
+        let hash = |list: EntityList<u32, u32, _>| {
+            let mut hasher = DefaultHasher::new();
+            list.hash_in(&pool, &mut hasher);
+            hasher.finish()
+        };
+        assert_eq!(hash(a), hash(b));
+        assert_ne!(hash(a), hash(c));
+
+        let mut dedup = HashMap::new();
+        for list in [a, c, b] {
+            dedup.entry(list.contents(&pool)).or_insert(list);
+        }
+        assert_eq!(dedup.len(), 2);
+        assert_eq!(dedup[&b.contents(&pool)], a);
+        assert_eq!(dedup[&c.contents(&pool)].contents(&pool).list(), c);
+    }
+
+    #[test]
+    fn bound_entity_lists() {
+        let mut pool: SlabPool<DefaultSlot<Vec<u32>>, u32> = SlabPool::new();
+        let mut list = EntityList::new(&mut pool);
+        let mut bound = list.bind_mut(&mut pool);
+        assert!(bound.is_empty());
+        bound.extend([3, 1, 2]);
+        bound.push(5);
+        bound[1] = 4;
+        bound.sort();
+        for elem in &mut *bound {
+            *elem *= 10;
+        }
+        assert_eq!(bound.pop(), Some(50));
+        bound.insert(0, 1);
+        assert_eq!(bound.remove(1), 20);
+        bound.retain(|x| *x != 40);
+        assert_eq!(bound.len(), 2);
+        assert_eq!(bound.get(5), None);
+        *bound.get_mut(0).unwrap() += 1;
+        assert_eq!(format!("{bound:?}"), "[2, 30]");
+        assert_eq!((&bound).into_iter().sum::<u32>(), 32);
+        bound.iter_mut().for_each(|elem| *elem += 1);
+        assert!(bound.iter().eq(&[3, 31]));
+        assert_eq!(bound.list(), list);
+
+        let bound = list.bind(&pool);
+        assert_eq!(bound[1], 31);
+        assert_eq!(bound.get(0), Some(&3));
+        assert_eq!(&*bound, [3, 31]);
+        assert_eq!(bound.first(), Some(&3));
+        assert_eq!(bound.into_iter().copied().collect::<Vec<_>>(), [3, 31]);
+        assert_eq!(format!("{bound:?}"), "[3, 31]");
+        assert_eq!(bound.contents(), list.contents(&pool));
+        assert_eq!(bound.list(), list);
+        assert!(std::ptr::eq(bound.pool(), &pool));
+        assert!(!bound.is_empty());
+
+        let mut bound = list.bind_mut(&mut pool);
+        bound.truncate(1);
+        assert_eq!(bound.as_ref().len(), 1);
+        bound.clear();
+        assert!(bound.into_iter().next().is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "insertion index (is 2) should be <= len (is 1)")]
+    fn entity_list_insert_out_of_bounds() {
+        let mut pool: SlabPool<DefaultSlot<Vec<u32>>, u32> = SlabPool::new();
+        let mut v = EntityList::new(&mut pool);
+        v.push(0, &mut pool);
+        v.insert(2, 1, &mut pool);
+    }
+
     #[test]
     fn basic_entity_list_usage() {
         let mut pool: SlabPool<DefaultSlot<Vec<u32>>, u32> = SlabPool::new();
@@ -259,4 +1225,17 @@ mod test {
         assert_eq!(v.partial_cmp(&u), Some(Ordering::Less));
         assert_eq!(v.cmp(&u), Ordering::Less);
     }
+
+    #[test]
+    fn entity_list_iter() {
+        let mut pool: SlabPool<DefaultSlot<Vec<u32>>, u32> = SlabPool::new();
+        let mut v = EntityList::new(&mut pool);
+        assert!(v.iter(&pool).next().is_none());
+        v.extend([1, 2, 3], &mut pool);
+        assert!(v.iter(&pool).eq(&[1, 2, 3]));
+        v.iter_mut(&mut pool).for_each(|elem| *elem *= 2);
+        assert_eq!(v.as_slice(&pool), &[2, 4, 6]);
+        assert_eq!(v.pop(&mut pool), Some(6));
+        assert!(v.iter(&pool).eq(&[2, 4]));
+    }
 }
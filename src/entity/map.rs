@@ -0,0 +1,214 @@
+/*!
+Maps backed by a pool
+*/
+
+use std::{fmt::Debug, hash::Hash, marker::PhantomData};
+
+use bytemuck::TransparentWrapper;
+
+use crate::pool::{
+    container::{map::MapPool, InsertEmpty, IsEmptyPool, LenPool},
+    Insert,
+};
+
+/// A map from indices of type `I` to values of type `V` backed by a pool of type `P`
+#[derive(TransparentWrapper)]
+#[repr(transparent)]
+#[transparent(K)]
+pub struct EntityMap<I, V, K, P> {
+    ix: K,
+    data: PhantomData<(I, V, P)>,
+}
+
+impl<I, V, K, P> Clone for EntityMap<I, V, K, P>
+where
+    K: Clone,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn clone(&self) -> Self {
+        Self {
+            ix: self.ix.clone(),
+            data: PhantomData,
+        }
+    }
+}
+
+impl<I, V, K, P> Copy for EntityMap<I, V, K, P> where K: Copy {}
+
+impl<I, V, K, P> Debug for EntityMap<I, V, K, P>
+where
+    K: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("EntityMap").field(&self.ix).finish()
+    }
+}
+
+impl<I, V, K, P> PartialEq for EntityMap<I, V, K, P>
+where
+    K: PartialEq,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn eq(&self, other: &Self) -> bool {
+        self.ix == other.ix
+    }
+}
+
+impl<I, V, K, P> Eq for EntityMap<I, V, K, P> where K: Eq {}
+
+impl<I, V, K, P> PartialOrd for EntityMap<I, V, K, P>
+where
+    K: PartialOrd,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.ix.partial_cmp(&other.ix)
+    }
+}
+
+impl<I, V, K, P> Ord for EntityMap<I, V, K, P>
+where
+    K: Ord,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.ix.cmp(&other.ix)
+    }
+}
+
+impl<I, V, K, P> Hash for EntityMap<I, V, K, P>
+where
+    K: Hash,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.ix.hash(state);
+    }
+}
+
+impl<I, V, K, P> EntityMap<I, V, K, P>
+where
+    K: Copy,
+    P: MapPool<K, I, V>,
+{
+    /// Create a new, empty map
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new(pool: &mut P) -> Self
+    where
+        P: InsertEmpty<K>,
+    {
+        EntityMap {
+            ix: pool.insert_empty(),
+            data: PhantomData,
+        }
+    }
+
+    /// Create a new map from the given initializer
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new_from<T>(value: T, pool: &mut P) -> Self
+    where
+        P: Insert<K, T>,
+    {
+        EntityMap {
+            ix: pool.insert(value),
+            data: PhantomData,
+        }
+    }
+
+    /// Get the number of entries in this map
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn len(&self, pool: &P) -> usize
+    where
+        P: LenPool<K>,
+    {
+        pool.key_len(self.ix)
+    }
+
+    /// Return `true` if this map has no entries
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn is_empty(&self, pool: &P) -> bool
+    where
+        P: IsEmptyPool<K>,
+    {
+        pool.key_is_empty(self.ix)
+    }
+
+    /// Insert an entry into this map, returning the value previously associated with `index`, if any
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn insert(&self, index: I, value: V, pool: &mut P) -> Option<V> {
+        pool.insert_entry(self.ix, index, value)
+    }
+
+    /// Remove an entry from this map, returning its value if it was present
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn remove(&self, index: &I, pool: &mut P) -> Option<V> {
+        pool.remove_entry(self.ix, index)
+    }
+
+    /// Get the value associated with `index` in this map
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get<'a>(&self, index: &I, pool: &'a P) -> Option<&'a V> {
+        pool.get_entry(self.ix, index)
+    }
+
+    /// Get the value associated with `index` in this map mutably
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get_mut<'a>(&self, index: &I, pool: &'a mut P) -> Option<&'a mut V> {
+        pool.get_entry_mut(self.ix, index)
+    }
+
+    /// Check whether this map has an entry for `index`
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn contains_key(&self, index: &I, pool: &P) -> bool {
+        pool.get_entry(self.ix, index).is_some()
+    }
+
+    /// Iterate over the entries of this map
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn iter<'a>(&self, pool: &'a P) -> P::Iter<'a> {
+        pool.iter_entries(self.ix)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeMap;
+
+    use crate::{
+        pool::{container::map::VecMap, slab::SlabPool},
+        slot::DefaultSlot,
+    };
+
+    use super::EntityMap;
+
+    #[test]
+    fn basic_entity_map_usage() {
+        let mut pool: SlabPool<DefaultSlot<VecMap<u16, &str>>, u32> = SlabPool::new();
+        let names = EntityMap::new(&mut pool);
+        let other = EntityMap::new(&mut pool);
+        assert!(names.is_empty(&pool));
+        assert_eq!(names.insert(7, "x", &mut pool), None);
+        assert_eq!(names.insert(2, "y", &mut pool), None);
+        assert_eq!(names.insert(7, "z", &mut pool), Some("x"));
+        assert_eq!(other.insert(2, "w", &mut pool), None);
+        assert_eq!(names.len(&pool), 2);
+        assert_eq!(names.get(&7, &pool), Some(&"z"));
+        assert!(names.contains_key(&2, &pool));
+        assert!(!names.contains_key(&3, &pool));
+        *names.get_mut(&2, &mut pool).unwrap() = "v";
+        assert!(names.iter(&pool).eq([(&2, &"v"), (&7, &"z")]));
+        assert_eq!(names.remove(&2, &mut pool), Some("v"));
+        assert_eq!(names.remove(&2, &mut pool), None);
+        assert_eq!(other.get(&2, &pool), Some(&"w"));
+
+        assert_eq!(format!("{names:?}"), "EntityMap(0)");
+        assert_ne!(names, other);
+        assert!(names < other);
+        assert_eq!(names.clone(), names);
+
+        let mut pool: SlabPool<DefaultSlot<BTreeMap<char, u8>>, u8> = SlabPool::new();
+        let map = EntityMap::new_from(BTreeMap::from([('a', 1), ('b', 2)]), &mut pool);
+        assert_eq!(map.get(&'b', &pool), Some(&2));
+        assert_eq!(map.len(&pool), 2);
+    }
+}
@@ -3,3 +3,5 @@ Entities backed by a pool
 */
 
 pub mod list;
+pub mod map;
+pub mod set;
@@ -0,0 +1,213 @@
+/*!
+Sets backed by a pool
+*/
+
+use std::{fmt::Debug, hash::Hash, marker::PhantomData};
+
+use bytemuck::TransparentWrapper;
+
+use crate::pool::{
+    container::{set::SetPool, ContainerPool, InsertEmpty, IsEmptyPool, LenPool},
+    Insert,
+};
+
+/// A set backed by a pool of type `P`
+#[derive(TransparentWrapper)]
+#[repr(transparent)]
+#[transparent(K)]
+pub struct EntitySet<T, K, P> {
+    ix: K,
+    data: PhantomData<(T, P)>,
+}
+
+impl<T, K, P> Clone for EntitySet<T, K, P>
+where
+    K: Clone,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn clone(&self) -> Self {
+        Self {
+            ix: self.ix.clone(),
+            data: PhantomData,
+        }
+    }
+}
+
+impl<T, K, P> Copy for EntitySet<T, K, P> where K: Copy {}
+
+impl<T, K, P> Debug for EntitySet<T, K, P>
+where
+    K: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("EntitySet").field(&self.ix).finish()
+    }
+}
+
+impl<T, K, P> PartialEq for EntitySet<T, K, P>
+where
+    K: PartialEq,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn eq(&self, other: &Self) -> bool {
+        self.ix == other.ix
+    }
+}
+
+impl<T, K, P> Eq for EntitySet<T, K, P> where K: Eq {}
+
+impl<T, K, P> PartialOrd for EntitySet<T, K, P>
+where
+    K: PartialOrd,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        self.ix.partial_cmp(&other.ix)
+    }
+}
+
+impl<T, K, P> Ord for EntitySet<T, K, P>
+where
+    K: Ord,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.ix.cmp(&other.ix)
+    }
+}
+
+impl<T, K, P> Hash for EntitySet<T, K, P>
+where
+    K: Hash,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.ix.hash(state);
+    }
+}
+
+impl<T, K, P> EntitySet<T, K, P>
+where
+    K: Copy,
+    P: ContainerPool<K, Elem = T>,
+{
+    /// Create a new, empty set
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new(pool: &mut P) -> Self
+    where
+        P: InsertEmpty<K>,
+    {
+        EntitySet {
+            ix: pool.insert_empty(),
+            data: PhantomData,
+        }
+    }
+
+    /// Create a new set from the given initializer
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new_from<V>(value: V, pool: &mut P) -> Self
+    where
+        P: Insert<K, V>,
+    {
+        EntitySet {
+            ix: pool.insert(value),
+            data: PhantomData,
+        }
+    }
+
+    /// Get the number of elements in this set
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn len(&self, pool: &P) -> usize
+    where
+        P: LenPool<K>,
+    {
+        pool.key_len(self.ix)
+    }
+
+    /// Return `true` if this set has no elements
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn is_empty(&self, pool: &P) -> bool
+    where
+        P: IsEmptyPool<K>,
+    {
+        pool.key_is_empty(self.ix)
+    }
+
+    /// Insert an element into this set, returning whether it was not already present
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn insert(&self, elem: T, pool: &mut P) -> bool
+    where
+        P: SetPool<K>,
+    {
+        pool.insert_elem(self.ix, elem)
+    }
+
+    /// Remove an element from this set, returning whether it was present
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn remove(&self, elem: &T, pool: &mut P) -> bool
+    where
+        P: SetPool<K>,
+    {
+        pool.remove_elem(self.ix, elem)
+    }
+
+    /// Check whether this set contains an element
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn contains(&self, elem: &T, pool: &P) -> bool
+    where
+        P: SetPool<K>,
+    {
+        pool.contains_elem(self.ix, elem)
+    }
+
+    /// Insert every element of `other` into this set, returning whether this set changed
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn union_with(&self, other: &Self, pool: &mut P) -> bool
+    where
+        P: SetPool<K>,
+    {
+        pool.union_into(self.ix, other.ix)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::BTreeSet;
+
+    use crate::{
+        pool::{container::set::BitSet, slab::SlabPool},
+        slot::DefaultSlot,
+    };
+
+    use super::EntitySet;
+
+    #[test]
+    fn basic_entity_set_usage() {
+        let mut pool: SlabPool<DefaultSlot<BitSet<u16>>, u32> = SlabPool::new();
+        let preds = EntitySet::new(&mut pool);
+        let live_in = EntitySet::new(&mut pool);
+        assert!(preds.is_empty(&pool));
+        assert!(preds.insert(3, &mut pool));
+        assert!(!preds.insert(3, &mut pool));
+        assert!(preds.insert(90, &mut pool));
+        assert!(preds.contains(&90, &pool));
+        assert!(!live_in.contains(&90, &pool));
+        assert!(live_in.union_with(&preds, &mut pool));
+        assert!(!live_in.union_with(&preds, &mut pool));
+        assert_eq!(live_in.len(&pool), 2);
+        assert!(preds.remove(&3, &mut pool));
+        assert!(!preds.remove(&3, &mut pool));
+        assert_eq!(preds.len(&pool), 1);
+        assert!(live_in.contains(&3, &pool));
+
+        assert_eq!(format!("{preds:?}"), "EntitySet(0)");
+        assert_ne!(preds, live_in);
+        assert!(preds < live_in);
+        assert_eq!(preds.clone(), preds);
+
+        let mut pool: SlabPool<DefaultSlot<BTreeSet<char>>, u8> = SlabPool::new();
+        let set = EntitySet::new_from(BTreeSet::from(['a', 'b']), &mut pool);
+        assert!(set.contains(&'a', &pool));
+        assert_eq!(set.len(&pool), 2);
+    }
+}
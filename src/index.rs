@@ -41,6 +41,13 @@ pub trait ContiguousIx: Copy + Eq + Ord + PartialOrd {
     }
 }
 
+/// A [`ContiguousIx`] whose implementation can be relied on by unsafe code
+///
+/// # Safety
+///
+/// For any `n` such that `Self::try_new(n)` returns `Some(i)`, `i.index()` must return `n`, for every copy of `i`.
+pub unsafe trait TrustedIx: ContiguousIx {}
+
 macro_rules! primitive_contiguous_ix {
     ($ty:ty) => {
         impl ContiguousIx for $ty {
@@ -79,6 +86,9 @@ macro_rules! primitive_contiguous_ix {
                 self == 0
             }
         }
+
+        // SAFETY: `try_new` only accepts indices up to `<$ty>::MAX`, which `index` casts back losslessly
+        unsafe impl TrustedIx for $ty {}
     };
 }
 
@@ -152,6 +162,9 @@ macro_rules! negative_contiguous_ix {
                 self.0 == -1
             }
         }
+
+        // SAFETY: `try_new` only accepts indices up to `<$ty>::MAX`, whose negation `index` inverts losslessly
+        unsafe impl TrustedIx for Neg<$ty> {}
     };
 }
 
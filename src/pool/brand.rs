@@ -0,0 +1,271 @@
+/*!
+Branded pools, whose keys are statically tied to a single pool instance
+
+A pool is branded by passing it to [`scope`], which hands a closure a [`BrandedPool<'id, P>`] for a fresh, invariant lifetime `'id`. Keys
+returned by the branded pool have type [`Branded<'id, K>`], and since no two calls to [`scope`] can produce the same `'id`, a key from one
+branded pool cannot be passed to another. As branded keys can only be created by inserting into their pool, and do not implement
+[`ContiguousIx`](crate::index::ContiguousIx), lookups into pools implementing [`GetUnchecked`] can skip bounds checks. The pools in this
+crate only implement [`GetUnchecked`] for keys implementing [`TrustedIx`](crate::index::TrustedIx), since a
+[`ContiguousIx`](crate::index::ContiguousIx) implementation whose `index` does not invert `try_new` could otherwise index out of bounds:
+
+```compile_fail
+use miniptr::{index::ContiguousIx, pool::{brand::scope, slab::SlabPool, GetRef, Insert}, slot::DefaultSlot};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct Offset(u32);
+
+impl ContiguousIx for Offset {
+    const MAX_INDEX: usize = u32::MAX as usize;
+
+    fn try_new(ix: usize) -> Option<Self> {
+        u32::try_from(ix).ok().map(Offset)
+    }
+
+    fn index(self) -> usize {
+        self.0 as usize + 1_000_000
+    }
+}
+
+let pool: SlabPool<DefaultSlot<u64>, Offset> = SlabPool::new();
+scope(pool, |mut pool| {
+    let key = pool.insert(7);
+    // Error: `Offset` is not a `TrustedIx`, so lookups by branded key are unavailable
+    pool.at(key);
+});
+```
+
+Branding itself prevents keys from being used with the wrong pool:
+
+```compile_fail
+use miniptr::{pool::{brand::scope, slab::SlabPool, GetRef, Insert}, slot::DefaultSlot};
+
+let a: SlabPool<DefaultSlot<u32>, u32> = SlabPool::new();
+let b: SlabPool<DefaultSlot<u32>, u32> = SlabPool::new();
+scope(a, |mut a| {
+    scope(b, |b| {
+        let key = a.insert(5);
+        // Error: `key` is branded with `a`'s lifetime
+        b.at(key);
+    })
+});
+```
+*/
+use std::{fmt::Debug, marker::PhantomData};
+
+use super::*;
+
+/// An invariant lifetime, which is neither shortened nor lengthened by subtyping
+type Brand<'id> = PhantomData<fn(&'id ()) -> &'id ()>;
+
+/// A key of type `K` branded with the lifetime `'id` of the unique [`BrandedPool`] which returned it
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Branded<'id, K> {
+    key: K,
+    brand: Brand<'id>,
+}
+
+impl<'id, K> Branded<'id, K> {
+    /// Get the underlying key, discarding the brand
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn key(self) -> K {
+        self.key
+    }
+}
+
+impl<'id, K> Debug for Branded<'id, K>
+where
+    K: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Branded").field(&self.key).finish()
+    }
+}
+
+/// A pool of type `P` branded with the lifetime `'id`, created by [`scope`]
+///
+/// Only the operations of [`Insert`], [`Pool`], [`Take`], [`GetRef`] and [`GetMut`] are available on the underlying pool while it is branded,
+/// so that keys it has returned stay in bounds.
+#[derive(Debug)]
+pub struct BrandedPool<'id, P> {
+    pool: P,
+    brand: Brand<'id>,
+}
+
+/// Brand a pool for the duration of `f`, returning its result
+///
+/// The branded pool can be unwrapped with [`BrandedPool::into_inner`] and returned from `f` to keep using it unbranded afterwards.
+#[cfg_attr(not(tarpaulin), inline(always))]
+pub fn scope<P, R>(pool: P, f: impl for<'id> FnOnce(BrandedPool<'id, P>) -> R) -> R {
+    f(BrandedPool {
+        pool,
+        brand: PhantomData,
+    })
+}
+
+impl<'id, P> BrandedPool<'id, P> {
+    /// Get a reference to the underlying pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn inner(&self) -> &P {
+        &self.pool
+    }
+
+    /// Unwrap this pool, invalidating all keys branded with it
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn into_inner(self) -> P {
+        self.pool
+    }
+}
+
+/// A pool which can look up a key without checking it is in bounds
+///
+/// # Safety
+///
+/// Given a key returned by [`Insert::try_insert`], `at_unchecked` must be sound to call after any sequence of calls to the methods of
+/// [`Insert`], [`Pool`], [`Take`], [`GetRef`] and [`GetMut`], even if the key has been deleted; a deleted key may return an arbitrary value or
+/// panic.
+pub unsafe trait GetUnchecked<K, V: ?Sized>: GetRef<K, V> {
+    /// Get a reference to the value associated with a key, without checking that it is in bounds
+    ///
+    /// # Safety
+    ///
+    /// `key` must have been returned by an insertion into this pool, as described in the trait documentation
+    #[must_use]
+    unsafe fn at_unchecked(&self, key: K) -> &V;
+}
+
+/// A pool which can look up a key mutably without checking it is in bounds
+///
+/// # Safety
+///
+/// As for [`GetUnchecked`], but for `at_unchecked_mut`
+pub unsafe trait GetUncheckedMut<K, V: ?Sized>: GetMut<K, V> {
+    /// Get a mutable reference to the value associated with a key, without checking that it is in bounds
+    ///
+    /// # Safety
+    ///
+    /// `key` must have been returned by an insertion into this pool, as described in the documentation of [`GetUnchecked`]
+    #[must_use]
+    unsafe fn at_unchecked_mut(&mut self, key: K) -> &mut V;
+}
+
+impl<'id, P, K, V> Insert<Branded<'id, K>, V> for BrandedPool<'id, P>
+where
+    P: Insert<K, V>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_insert(&mut self, val: V) -> Result<Branded<'id, K>, V> {
+        let key = self.pool.try_insert(val)?;
+        Ok(Branded {
+            key,
+            brand: PhantomData,
+        })
+    }
+}
+
+impl<'id, P, K> Pool<Branded<'id, K>> for BrandedPool<'id, P>
+where
+    P: Pool<K>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn delete(&mut self, key: Branded<'id, K>) {
+        self.pool.delete(key.key)
+    }
+}
+
+impl<'id, P, K> ObjectPool<Branded<'id, K>> for BrandedPool<'id, P>
+where
+    P: ObjectPool<K>,
+{
+    type Object = P::Object;
+}
+
+impl<'id, P, K> SafeFreePool<Branded<'id, K>> for BrandedPool<'id, P> where P: SafeFreePool<K> {}
+impl<'id, P, K> DoubleFreePool<Branded<'id, K>> for BrandedPool<'id, P> where P: DoubleFreePool<K> {}
+
+impl<'id, P, K, V> Take<Branded<'id, K>, V> for BrandedPool<'id, P>
+where
+    P: Take<K, V>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_take(&mut self, key: Branded<'id, K>) -> Option<V> {
+        self.pool.try_take(key.key)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn take(&mut self, key: Branded<'id, K>) -> V {
+        self.pool.take(key.key)
+    }
+}
+
+impl<'id, P, K, V: ?Sized> GetRef<Branded<'id, K>, V> for BrandedPool<'id, P>
+where
+    P: GetUnchecked<K, V>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at(&self, key: Branded<'id, K>) -> Option<&V> {
+        Some(self.at(key))
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn at(&self, key: Branded<'id, K>) -> &V {
+        // SAFETY: a key branded with `'id` can only have been returned by an insertion into this pool, which has only been accessed through
+        // the traits allowed by `GetUnchecked`
+        unsafe { self.pool.at_unchecked(key.key) }
+    }
+}
+
+impl<'id, P, K, V: ?Sized> GetMut<Branded<'id, K>, V> for BrandedPool<'id, P>
+where
+    P: GetUncheckedMut<K, V>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at_mut(&mut self, key: Branded<'id, K>) -> Option<&mut V> {
+        Some(self.at_mut(key))
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn at_mut(&mut self, key: Branded<'id, K>) -> &mut V {
+        // SAFETY: as for `GetRef::at`
+        unsafe { self.pool.at_unchecked_mut(key.key) }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        pool::{container::stack::StackPool, slab::SlabPool},
+        slot::DefaultSlot,
+    };
+
+    #[test]
+    fn branded_slab_pool() {
+        let pool: SlabPool<DefaultSlot<u32>, u32> = SlabPool::new();
+        let pool = scope(pool, |mut pool| {
+            let a = pool.insert(1);
+            let b = pool.insert(2);
+            *pool.at_mut(b) += 1;
+            assert_eq!(pool.at(a), &1);
+            assert_eq!(pool.try_at(b), Some(&3));
+            assert_eq!(pool.take(a), 1);
+            let c = pool.insert(4);
+            assert_eq!(c.key(), a.key());
+            assert_eq!(format!("{c:?}"), "Branded(0)");
+            pool.delete(b);
+            assert_eq!(pool.inner().free_slots(), 1);
+            pool.into_inner()
+        });
+        assert_eq!(pool.total_slots(), 2);
+    }
+
+    #[test]
+    fn branded_container_pools() {
+        let pool: Arena<Vec<Vec<u8>>, u16> = Arena::from(Vec::new());
+        scope(pool, |mut pool| {
+            let key = pool.insert(vec![1]);
+            pool.push(key, 2);
+            assert_eq!(pool.pop(key), Some(2));
+            assert_eq!(pool.at(key), &[1]);
+        });
+    }
+}
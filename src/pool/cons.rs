@@ -0,0 +1,316 @@
+/*!
+A hash-consing arena for DAGs of nodes whose children are keys into the same arena
+*/
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hash},
+    ops::Index,
+};
+
+use crate::index::ContiguousIx;
+
+use super::{intern::InternPool, *};
+
+/// A node in a [`ConsArena`], having children of key type `K`
+pub trait Children<K> {
+    /// Call `f` on each child of this node, in order
+    fn for_each_child<F: FnMut(K)>(&self, f: F);
+}
+
+impl<K: Copy> Children<K> for [K] {
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn for_each_child<F: FnMut(K)>(&self, f: F) {
+        self.iter().copied().for_each(f)
+    }
+}
+
+impl<K: Copy> Children<K> for Vec<K> {
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn for_each_child<F: FnMut(K)>(&self, f: F) {
+        self.as_slice().for_each_child(f)
+    }
+}
+
+impl<K: Copy, const N: usize> Children<K> for [K; N] {
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn for_each_child<F: FnMut(K)>(&self, f: F) {
+        self.as_slice().for_each_child(f)
+    }
+}
+
+/// A hash-consing arena of nodes of type `N`, whose children are keys of type `K` into the same arena
+///
+/// [`ConsArena::mk`] returns the existing key for any node equal to one already in the arena, and so structurally equal subterms always receive the same key.
+/// Since a node's children must be created before the node itself, every child has a smaller key than its parent, and hence key order is a topological
+/// order of the DAG, with children first.
+#[derive(Debug, Clone)]
+pub struct ConsArena<N, K = usize, S = RandomState> {
+    nodes: InternPool<N, K, S>,
+}
+
+impl<N, K, S: Default> Default for ConsArena<N, K, S> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn default() -> Self {
+        ConsArena {
+            nodes: InternPool::default(),
+        }
+    }
+}
+
+impl<N, K> ConsArena<N, K> {
+    /// Create a new, empty arena
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new() -> ConsArena<N, K> {
+        Self::default()
+    }
+}
+
+impl<N, K, S> ConsArena<N, K, S> {
+    /// Create a new, empty arena which hashes nodes using `hasher`
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn with_hasher(hasher: S) -> ConsArena<N, K, S> {
+        ConsArena {
+            nodes: InternPool::with_hasher(hasher),
+        }
+    }
+
+    /// Get the number of distinct nodes in this arena
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Check whether this arena is empty
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// Get every node in this arena in topological order, with children before their parents
+    ///
+    /// The node for key `k` is at index `k.index()`.
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn nodes(&self) -> &[N] {
+        self.nodes.values()
+    }
+
+    /// Get the underlying interning pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn intern_pool(&self) -> &InternPool<N, K, S> {
+        &self.nodes
+    }
+}
+
+impl<N, K, S> ConsArena<N, K, S>
+where
+    N: Children<K>,
+    K: ContiguousIx,
+{
+    /// Get the keys of every node reachable from `roots`, in topological order, with children before their parents
+    pub fn reachable(&self, roots: impl IntoIterator<Item = K>) -> Vec<K> {
+        let mut marked = vec![false; self.len()];
+        let mut max = 0;
+        for root in roots {
+            let ix = root.index();
+            marked[ix] = true;
+            max = max.max(ix + 1);
+        }
+        // Children always have smaller keys than their parents, so a single descending pass marks everything reachable
+        for ix in (0..max).rev() {
+            if marked[ix] {
+                self.nodes()[ix].for_each_child(|child| marked[child.index()] = true);
+            }
+        }
+        (0..max)
+            .filter(|ix| marked[*ix])
+            .map(K::new_unchecked)
+            .collect()
+    }
+
+    /// Compute a value for every node reachable from `roots`, bottom-up, calling `f` exactly once per node
+    ///
+    /// `f` is passed the key of the node, the node itself, and the values computed so far, which are guaranteed to include the node's children.
+    pub fn fold_many<T, F>(&self, roots: impl IntoIterator<Item = K>, mut f: F) -> Memo<T>
+    where
+        F: FnMut(K, &N, &Memo<T>) -> T,
+    {
+        let mut memo = Memo(Vec::new());
+        memo.0.resize_with(self.len(), || None);
+        for key in self.reachable(roots) {
+            let value = f(key, &self.nodes()[key.index()], &memo);
+            memo.0[key.index()] = Some(value);
+        }
+        memo
+    }
+
+    /// Compute a value for `root`, bottom-up, calling `f` exactly once on every node reachable from `root`
+    ///
+    /// `f` is passed the key of the node, the node itself, and the values computed so far, which are guaranteed to include the node's children.
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn fold<T, F>(&self, root: K, f: F) -> T
+    where
+        F: FnMut(K, &N, &Memo<T>) -> T,
+    {
+        let mut memo = self.fold_many([root], f);
+        memo.0[root.index()].take().unwrap()
+    }
+}
+
+impl<N, K, S> ConsArena<N, K, S>
+where
+    N: Children<K> + Hash + Eq,
+    K: ContiguousIx,
+    S: BuildHasher,
+{
+    /// Make a node, returning the key of an equal node if one already exists
+    ///
+    /// Panics if any of the node's children is not a key in this arena, or if the arena is out of keys
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn mk(&mut self, node: N) -> K {
+        match self.try_mk(node) {
+            Ok(key) => key,
+            Err(_) => panic!("arena is out of keys"),
+        }
+    }
+
+    /// Make a node, returning the key of an equal node if one already exists
+    ///
+    /// Returns the node back if it does not already exist and the arena is out of keys.
+    /// Panics if any of the node's children is not a key in this arena.
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn try_mk(&mut self, node: N) -> Result<K, N> {
+        let len = self.len();
+        node.for_each_child(|child| {
+            assert!(
+                child.index() < len,
+                "child {} is not a key in this arena",
+                child.index()
+            )
+        });
+        self.nodes.try_intern(node)
+    }
+
+    /// Get the key of a node equal to `node`, or `None` if no such node exists
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get_key(&self, node: &N) -> Option<K> {
+        self.nodes.get_key(node)
+    }
+}
+
+impl<N, K, S> Index<K> for ConsArena<N, K, S>
+where
+    K: ContiguousIx,
+{
+    type Output = N;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn index(&self, index: K) -> &N {
+        &self.nodes[index]
+    }
+}
+
+impl<N, K, S> GetRef<K, N> for ConsArena<N, K, S>
+where
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at(&self, key: K) -> Option<&N> {
+        self.nodes.try_at(key)
+    }
+}
+
+/// The values computed by [`ConsArena::fold_many`], indexed by key
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+pub struct Memo<T>(Vec<Option<T>>);
+
+impl<T> Memo<T> {
+    /// Get the value computed for `key`, or `None` if it has not been computed
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get<K: ContiguousIx>(&self, key: K) -> Option<&T> {
+        self.0.get(key.index())?.as_ref()
+    }
+}
+
+impl<T, K: ContiguousIx> Index<K> for Memo<T> {
+    type Output = T;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn index(&self, index: K) -> &T {
+        self.get(index).expect("value to have been computed")
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[derive(Debug, Clone, Eq, PartialEq, Hash)]
+    enum Term {
+        Var(char),
+        Add(u32, u32),
+        Mul(u32, u32),
+    }
+
+    impl Children<u32> for Term {
+        fn for_each_child<F: FnMut(u32)>(&self, mut f: F) {
+            if let Term::Add(l, r) | Term::Mul(l, r) = self {
+                f(*l);
+                f(*r);
+            }
+        }
+    }
+
+    #[test]
+    fn cons_arena_sharing() {
+        let mut arena: ConsArena<Term, u32> = ConsArena::new();
+        let x = arena.mk(Term::Var('x'));
+        let y = arena.mk(Term::Var('y'));
+        let sum = arena.mk(Term::Add(x, y));
+        let x2 = arena.mk(Term::Var('x'));
+        assert_eq!(x, x2);
+        let sum2 = arena.mk(Term::Add(x2, y));
+        assert_eq!(sum, sum2);
+        let sq = arena.mk(Term::Mul(sum, sum2));
+        let z = arena.mk(Term::Var('z'));
+        assert_eq!(arena.len(), 5);
+        assert_eq!(arena.get_key(&Term::Add(x, y)), Some(sum));
+        assert_eq!(arena.get_key(&Term::Add(y, x)), None);
+        assert_eq!(arena[sq], Term::Mul(sum, sum));
+        assert_eq!(arena.at(z), &Term::Var('z'));
+
+        assert_eq!(arena.reachable([sq]), [x, y, sum, sq]);
+        assert_eq!(arena.reachable([z, x]), [x, z]);
+        assert_eq!(arena.nodes().len(), 5);
+
+        // Evaluate (x + y) * (x + y) with x = 2, y = 3, visiting the shared subterm only once
+        let mut visits = 0;
+        let value = arena.fold(sq, |_, term, memo: &Memo<u64>| {
+            visits += 1;
+            match term {
+                Term::Var('x') => 2,
+                Term::Var(_) => 3,
+                Term::Add(l, r) => memo[*l] + memo[*r],
+                Term::Mul(l, r) => memo[*l] * memo[*r],
+            }
+        });
+        assert_eq!(value, 25);
+        assert_eq!(visits, 4);
+
+        let sizes = arena.fold_many([sq, z], |_, term, memo: &Memo<usize>| {
+            let mut size = 1;
+            term.for_each_child(|child| size += memo[child]);
+            size
+        });
+        assert_eq!(sizes[sq], 7);
+        assert_eq!(sizes[z], 1);
+        assert_eq!(sizes.get(sum), Some(&3));
+    }
+
+    #[test]
+    #[should_panic(expected = "child 3 is not a key in this arena")]
+    fn cons_arena_dangling_child() {
+        let mut arena: ConsArena<Vec<u32>, u32> = ConsArena::new();
+        let leaf = arena.mk(vec![]);
+        let _ = arena.mk(vec![leaf, 3]);
+    }
+}
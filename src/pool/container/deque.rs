@@ -0,0 +1,275 @@
+/*!
+Traits for containers implementing double-ended queues
+*/
+
+use super::{stack::StackLike, stack::StackPool, *};
+use std::collections::VecDeque;
+
+/// A [`Pool`] allocating double-ended queues containing elements of type `Self::Elem`
+///
+/// The back of a deque is its top as a stack, and so the `*_back` methods default to the corresponding methods of [`StackPool`].
+pub trait DequePool<K>: StackPool<K> {
+    /// Push an element to the front of a deque
+    ///
+    /// On success, returns the deque's key, which may have been changed (in this case, the old key should be considered deleted).
+    /// On failure, panics
+    ///
+    /// Fails if:
+    /// - The pool is out of capacity
+    ///
+    /// Leaves the pool in an unspecified state and returns an unspecified value or panics if used on an unrecognized key
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[must_use]
+    fn into_pushed_front(&mut self, key: K, item: Self::Elem) -> K {
+        self.try_into_pushed_front(key, item)
+            .ok()
+            .expect("failed to move-push to front of deque")
+    }
+
+    /// Push an element to the back of a deque
+    ///
+    /// Equivalent to [`StackPool::into_pushed`]
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[must_use]
+    fn into_pushed_back(&mut self, key: K, item: Self::Elem) -> K {
+        self.into_pushed(key, item)
+    }
+
+    /// Pop an element from the front of a deque, returning a (potentially new) key for the deque as well as the popped value.
+    ///
+    /// Returns `None` and leaves the deque unchanged given a key for an empty deque.
+    /// Otherwise, returns the old value and the new key; the old key (if different from the new key) should be considered deleted.
+    /// Panics on failure.
+    ///
+    /// Fails if:
+    /// - The pool is out of capacity, and moving the deque would require an allocation
+    ///
+    /// Leaves the pool in an unspecified state and returns an unspecified value or panics if used on an unrecognized key
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[must_use]
+    fn into_popped_front(&mut self, key: K) -> Option<(K, Self::Elem)> {
+        self.try_into_popped_front(key)
+            .expect("failed to move-pop from front of deque")
+    }
+
+    /// Pop an element from the back of a deque, returning a (potentially new) key for the deque as well as the popped value.
+    ///
+    /// Equivalent to [`StackPool::into_popped`]
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[must_use]
+    fn into_popped_back(&mut self, key: K) -> Option<(K, Self::Elem)> {
+        self.into_popped(key)
+    }
+
+    /// Try to push an element to the front of a deque, returning a (potentially new) key for the deque
+    ///
+    /// On success, returns the deque's key, which may have been changed (in this case, the old key should be considered deleted).
+    /// On failure, returns the item, leaving the deque unchanged.
+    ///
+    /// Fails if:
+    /// - The pool is out of capacity
+    ///
+    /// Leaves the pool in an unspecified state and returns an unspecified value or panics if used on an unrecognized key
+    fn try_into_pushed_front(&mut self, key: K, item: Self::Elem) -> Result<K, Self::Elem>;
+
+    /// Try to pop an element from the front of a deque, returning a (potentially new) key for the deque as well as the popped value.
+    ///
+    /// Returns `None` and leaves the deque unchanged given a key for an empty deque.
+    /// Otherwise, returns the old value and the new key; the old key (if different from the new key) should be considered deleted.
+    ///
+    /// Fails if:
+    /// - The pool is out of capacity, and moving the deque would require an allocation
+    ///
+    /// Leaves the pool in an unspecified state and returns an unspecified value or panics if used on an unrecognized key
+    fn try_into_popped_front(&mut self, key: K) -> Result<Option<(K, Self::Elem)>, ()>;
+
+    /// Push an element to the front of a deque
+    ///
+    /// Panics on failure
+    ///
+    /// Fails if:
+    /// - The pool is out of capacity
+    /// - Pushing an element to the deque would require moving the deque
+    ///
+    /// Leaves the pool in an unspecified state and returns an unspecified value or panics if used on an unrecognized key
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn push_front(&mut self, key: K, item: Self::Elem) {
+        self.try_push_front(key, item)
+            .ok()
+            .expect("failed to push to front of deque")
+    }
+
+    /// Push an element to the back of a deque
+    ///
+    /// Equivalent to [`StackPool::push`]
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn push_back(&mut self, key: K, item: Self::Elem) {
+        self.push(key, item)
+    }
+
+    /// Pop an element from the front of a deque
+    ///
+    /// On success, returns the popped value.
+    /// When called on an empty deque, returns `None`, leaving the deque unchanged.
+    /// Panics on failure.
+    ///
+    /// Fails if:
+    /// - Popping an element from the deque would require moving the deque
+    ///
+    /// Leaves the pool in an unspecified state and returns an unspecified value or panics if used on an unrecognized key
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn pop_front(&mut self, key: K) -> Option<Self::Elem> {
+        self.try_pop_front(key)
+            .expect("failed to pop from front of deque")
+    }
+
+    /// Pop an element from the back of a deque
+    ///
+    /// Equivalent to [`StackPool::pop`]
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn pop_back(&mut self, key: K) -> Option<Self::Elem> {
+        self.pop(key)
+    }
+
+    /// Try to push an element to the front of a deque
+    ///
+    /// On failure, returns the item, leaving the deque unchanged
+    ///
+    /// Fails if:
+    /// - The pool is out of capacity
+    /// - Pushing an element to the deque would require moving the deque
+    ///
+    /// Leaves the pool in an unspecified state and returns an unspecified value or panics if used on an unrecognized key
+    fn try_push_front(&mut self, key: K, item: Self::Elem) -> Result<(), Self::Elem>;
+
+    /// Try to push an element to the back of a deque
+    ///
+    /// Equivalent to [`StackPool::try_push`]
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_push_back(&mut self, key: K, item: Self::Elem) -> Result<(), Self::Elem> {
+        self.try_push(key, item)
+    }
+
+    /// Try to pop an element from the front of a deque
+    ///
+    /// On success, returns the popped value.
+    /// When called on an empty deque, returns `Ok(None)`, leaving the deque unchanged.
+    /// On failure, returns `Err(())`.
+    ///
+    /// Fails if:
+    /// - Popping an element from the deque would require moving the deque
+    ///
+    /// Leaves the pool in an unspecified state and returns an unspecified value or panics if used on an unrecognized key
+    fn try_pop_front(&mut self, key: K) -> Result<Option<Self::Elem>, ()>;
+
+    /// Try to pop an element from the back of a deque
+    ///
+    /// Equivalent to [`StackPool::try_pop`]
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_pop_back(&mut self, key: K) -> Result<Option<Self::Elem>, ()> {
+        self.try_pop(key)
+    }
+}
+
+/// A trait implemented by things which can be pushed to and popped from at both ends
+///
+/// The back of a deque is its top as a [`StackLike`]
+pub trait DequeLike: StackLike {
+    /// Push an element to the front of a deque
+    ///
+    /// Panics if:
+    /// - The deque is out of capacity and more cannot be allocated
+    fn push_front_deque(&mut self, item: Self::Elem);
+
+    /// Pop an element from the front of a deque
+    ///
+    /// On success, returns the popped value
+    /// When called on an empty deque, returns `None`, leaving the deque unchanged.
+    fn pop_front_deque(&mut self) -> Option<Self::Elem>;
+
+    /// Try to push an element to the front of a deque
+    ///
+    /// On success, returns `Ok(())`
+    /// On failure, returns the item, leaving the deque unchanged.
+    ///
+    /// Fails if:
+    /// - The deque is out of capacity and more cannot be allocated
+    fn try_push_front_deque(&mut self, item: Self::Elem) -> Result<(), Self::Elem>;
+}
+
+impl<P, K> DequePool<K> for P
+where
+    P: InsertPool<K> + PoolMut<K> + PoolRef<K>,
+    K: Clone,
+    P::Object: DequeLike,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_into_pushed_front(&mut self, key: K, item: Self::Elem) -> Result<K, Self::Elem> {
+        self.at_mut(key.clone())
+            .try_push_front_deque(item)
+            .map(|_| key)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_into_popped_front(&mut self, key: K) -> Result<Option<(K, Self::Elem)>, ()> {
+        Ok(self.at_mut(key.clone()).pop_front_deque().map(|v| (key, v)))
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_push_front(&mut self, key: K, item: Self::Elem) -> Result<(), Self::Elem> {
+        self.at_mut(key).try_push_front_deque(item)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_pop_front(&mut self, key: K) -> Result<Option<Self::Elem>, ()> {
+        Ok(self.at_mut(key).pop_front_deque())
+    }
+}
+
+impl<V> DequeLike for VecDeque<V> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn push_front_deque(&mut self, item: Self::Elem) {
+        self.push_front(item)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn pop_front_deque(&mut self) -> Option<Self::Elem> {
+        self.pop_front()
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_push_front_deque(&mut self, item: Self::Elem) -> Result<(), Self::Elem> {
+        self.push_front(item);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{pool::slab::SlabPool, slot::DefaultSlot};
+
+    #[test]
+    fn vec_deque_slab_pool() {
+        let mut pool: SlabPool<DefaultSlot<VecDeque<u32>>, u8> = SlabPool::new();
+        let a: u8 = pool.insert(VecDeque::new());
+        pool.push_front(a, 2);
+        pool.push_back(a, 3);
+        pool.push_front(a, 1);
+        assert_eq!(pool.into_pushed_front(a, 0), a);
+        assert_eq!(pool.into_pushed_back(a, 4), a);
+        assert_eq!(pool.try_push_front(a, 5), Ok(()));
+        assert_eq!(pool.try_push_back(a, 6), Ok(()));
+        assert_eq!(pool.at(a), &[5, 0, 1, 2, 3, 4, 6]);
+        assert_eq!(pool.pop_front(a), Some(5));
+        assert_eq!(pool.pop_back(a), Some(6));
+        assert_eq!(pool.into_popped_front(a), Some((a, 0)));
+        assert_eq!(pool.into_popped_back(a), Some((a, 4)));
+        assert_eq!(pool.try_pop_front(a), Ok(Some(1)));
+        assert_eq!(pool.try_pop_back(a), Ok(Some(3)));
+        assert_eq!(pool.pop_front(a), Some(2));
+        assert_eq!(pool.pop_front(a), None);
+        assert_eq!(pool.pop_back(a), None);
+        assert_eq!(pool.into_popped_front(a), None);
+    }
+}
@@ -0,0 +1,225 @@
+/*!
+Traits for iterating over the elements of containers
+*/
+
+use std::collections::{BTreeSet, VecDeque};
+
+use super::*;
+
+/// A [`Pool`] allocating containers whose elements can be iterated over by reference
+pub trait IterPool<K>: ContainerPool<K> {
+    /// An iterator over the elements of a container in this pool
+    type Iter<'a>: Iterator<Item = &'a Self::Elem>
+    where
+        Self: 'a,
+        Self::Elem: 'a;
+
+    /// Iterate over the elements of the container with key `key`
+    ///
+    /// Returns an unspecified iterator or panics if used on an unrecognized key
+    #[must_use]
+    fn iter_at(&self, key: K) -> Self::Iter<'_>;
+}
+
+/// A [`Pool`] allocating containers whose elements can be iterated over by mutable reference
+pub trait IterMutPool<K>: IterPool<K> {
+    /// An iterator over mutable references to the elements of a container in this pool
+    type IterMut<'a>: Iterator<Item = &'a mut Self::Elem>
+    where
+        Self: 'a,
+        Self::Elem: 'a;
+
+    /// Iterate mutably over the elements of the container with key `key`
+    ///
+    /// Returns an unspecified iterator or panics if used on an unrecognized key
+    #[must_use]
+    fn iter_at_mut(&mut self, key: K) -> Self::IterMut<'_>;
+}
+
+/// A container whose elements can be iterated over by reference
+///
+/// Containers which do not store their elements, such as a [`BitSet`](super::set::BitSet), cannot implement this trait.
+pub trait Iterable: Container {
+    /// An iterator over the elements of this container
+    type Iter<'a>: Iterator<Item = &'a Self::Elem>
+    where
+        Self: 'a;
+
+    /// Iterate over the elements of this container
+    fn iter_elems(&self) -> Self::Iter<'_>;
+}
+
+/// A container whose elements can be iterated over by mutable reference
+pub trait IterableMut: Iterable {
+    /// An iterator over mutable references to the elements of this container
+    type IterMut<'a>: Iterator<Item = &'a mut Self::Elem>
+    where
+        Self: 'a;
+
+    /// Iterate mutably over the elements of this container
+    fn iter_elems_mut(&mut self) -> Self::IterMut<'_>;
+}
+
+impl<P, K> IterPool<K> for P
+where
+    P: PoolRef<K>,
+    P::Object: Iterable + 'static, //TODO: relax this?
+{
+    type Iter<'a>
+        = <P::Object as Iterable>::Iter<'a>
+    where
+        Self: 'a,
+        Self::Elem: 'a;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn iter_at(&self, key: K) -> Self::Iter<'_> {
+        self.at(key).iter_elems()
+    }
+}
+
+impl<P, K> IterMutPool<K> for P
+where
+    P: PoolRef<K> + PoolMut<K>,
+    P::Object: IterableMut + 'static, //TODO: relax this?
+{
+    type IterMut<'a>
+        = <P::Object as IterableMut>::IterMut<'a>
+    where
+        Self: 'a,
+        Self::Elem: 'a;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn iter_at_mut(&mut self, key: K) -> Self::IterMut<'_> {
+        self.at_mut(key).iter_elems_mut()
+    }
+}
+
+/// Implement [`Iterable`] and [`IterableMut`] for a type dereferencing to a slice
+macro_rules! slice_iterable {
+    ($ty:ty, $($gen:tt)*) => {
+        impl<$($gen)*> Iterable for $ty {
+            type Iter<'a> = std::slice::Iter<'a, Self::Elem> where Self: 'a;
+
+            #[cfg_attr(not(tarpaulin), inline(always))]
+            fn iter_elems(&self) -> Self::Iter<'_> {
+                self.iter()
+            }
+        }
+
+        impl<$($gen)*> IterableMut for $ty {
+            type IterMut<'a> = std::slice::IterMut<'a, Self::Elem> where Self: 'a;
+
+            #[cfg_attr(not(tarpaulin), inline(always))]
+            fn iter_elems_mut(&mut self) -> Self::IterMut<'_> {
+                self.iter_mut()
+            }
+        }
+    };
+}
+
+slice_iterable!([V], V);
+slice_iterable!(Vec<V>, V);
+slice_iterable!([V; N], V, const N: usize);
+#[cfg(feature = "smallvec")]
+slice_iterable!(smallvec::SmallVec<A>, A: smallvec::Array);
+#[cfg(feature = "arrayvec")]
+slice_iterable!(arrayvec::ArrayVec<V, N>, V, const N: usize);
+
+impl<V> Iterable for VecDeque<V> {
+    type Iter<'a>
+        = std::collections::vec_deque::Iter<'a, V>
+    where
+        Self: 'a;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn iter_elems(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+}
+
+impl<V> IterableMut for VecDeque<V> {
+    type IterMut<'a>
+        = std::collections::vec_deque::IterMut<'a, V>
+    where
+        Self: 'a;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn iter_elems_mut(&mut self) -> Self::IterMut<'_> {
+        self.iter_mut()
+    }
+}
+
+impl<V> Iterable for BTreeSet<V> {
+    type Iter<'a>
+        = std::collections::btree_set::Iter<'a, V>
+    where
+        Self: 'a;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn iter_elems(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+}
+
+#[cfg(feature = "ecow")]
+impl<V> Iterable for ecow::EcoVec<V> {
+    type Iter<'a>
+        = std::slice::Iter<'a, V>
+    where
+        Self: 'a;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn iter_elems(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+}
+
+/// Mutable iteration over an [`ecow::EcoVec`] first makes it unique, cloning its elements if it is shared
+#[cfg(feature = "ecow")]
+impl<V: Clone> IterableMut for ecow::EcoVec<V> {
+    type IterMut<'a>
+        = std::slice::IterMut<'a, V>
+    where
+        Self: 'a;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn iter_elems_mut(&mut self) -> Self::IterMut<'_> {
+        self.make_mut().iter_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{pool::slab::SlabPool, slot::DefaultSlot};
+
+    fn iter_pool_test<C>(elems: C)
+    where
+        C: IterableMut<Elem = u32> + Default + 'static,
+    {
+        let mut pool: SlabPool<DefaultSlot<C>, u8> = SlabPool::new();
+        let key: u8 = pool.insert(elems);
+        assert_eq!(pool.iter_at(key).copied().collect::<Vec<_>>(), [1, 2, 3]);
+        for elem in pool.iter_at_mut(key) {
+            *elem *= 2;
+        }
+        assert_eq!(pool.iter_at(key).sum::<u32>(), 12);
+    }
+
+    #[test]
+    fn iter_pools() {
+        iter_pool_test(vec![1, 2, 3]);
+        iter_pool_test([1, 2, 3]);
+        iter_pool_test(VecDeque::from([1, 2, 3]));
+        #[cfg(feature = "smallvec")]
+        iter_pool_test(smallvec::SmallVec::<[u32; 2]>::from_slice(&[1, 2, 3]));
+        #[cfg(feature = "arrayvec")]
+        iter_pool_test(arrayvec::ArrayVec::<u32, 4>::try_from(&[1, 2, 3][..]).unwrap());
+        #[cfg(feature = "ecow")]
+        iter_pool_test(ecow::EcoVec::from([1, 2, 3]));
+
+        let mut pool: SlabPool<DefaultSlot<BTreeSet<u32>>, u8> = SlabPool::new();
+        let key: u8 = pool.insert(BTreeSet::from([3, 1, 2]));
+        assert_eq!(pool.iter_at(key).copied().collect::<Vec<_>>(), [1, 2, 3]);
+    }
+}
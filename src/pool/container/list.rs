@@ -0,0 +1,1063 @@
+/*!
+Intrusive doubly linked lists whose nodes live in a single [`SlabPool`]
+*/
+use crate::{index::ContiguousIx, slot::DefaultSlot};
+
+use super::super::slab::{FreeList, KeyList, SlabPool};
+use super::*;
+
+/// A node of a [`LinkedListPool`], which is either an element of a list or the head of a list
+///
+/// Lists are circular: the head of a list is a sentinel node with no value, whose successor is the first element of the list and whose
+/// predecessor is the last. An element which is not in any list is its own predecessor and successor.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Node<V, K> {
+    value: Option<V>,
+    prev: K,
+    next: K,
+    /// The head of the list this node belongs to, tracked in debug builds only
+    #[cfg(debug_assertions)]
+    list: Option<K>,
+}
+
+/// A dangling node without a value, left behind in the slots of removed nodes
+impl<V, K> Default for Node<V, K>
+where
+    K: Default,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn default() -> Self {
+        Node {
+            value: None,
+            prev: K::default(),
+            next: K::default(),
+            #[cfg(debug_assertions)]
+            list: None,
+        }
+    }
+}
+
+impl<V, K> Node<V, K> {
+    /// Create a node linked only to itself
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn new(value: Option<V>, key: K) -> Node<V, K>
+    where
+        K: Copy,
+    {
+        Node {
+            value,
+            prev: key,
+            next: key,
+            #[cfg(debug_assertions)]
+            list: None,
+        }
+    }
+}
+
+/// A pool of intrusive doubly linked lists, supporting constant time insertion, unlinking and splicing at any position
+///
+/// Both elements and the heads of lists are nodes allocated in a single [`SlabPool`], and so are identified by keys of type `K`.
+/// An element is inserted unlinked with [`Insert::insert`], and may then be linked into at most one list at a time; in debug builds,
+/// linking an element which already belongs to a list, or using an element as a position in a list it does not belong to, panics.
+/// Keeping track of membership makes [`LinkedListPool::splice`], [`LinkedListPool::append`] and [`LinkedListPool::split_off`] take time
+/// linear in the number of moved elements in debug builds; in release builds, they take constant time.
+///
+/// A list is deleted along with all of its elements by [`Pool::delete`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LinkedListPool<V, K = usize, F = KeyList<K>> {
+    nodes: SlabPool<DefaultSlot<Node<V, K>>, K, F>,
+}
+
+impl<V, K, F> Default for LinkedListPool<V, K, F>
+where
+    K: ContiguousIx,
+    F: FreeList<[DefaultSlot<Node<V, K>>], K> + Default,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V, K, F> LinkedListPool<V, K, F>
+where
+    K: ContiguousIx,
+    F: FreeList<[DefaultSlot<Node<V, K>>], K>,
+{
+    /// Create a new, empty pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new() -> LinkedListPool<V, K, F>
+    where
+        F: Default,
+    {
+        LinkedListPool {
+            nodes: SlabPool::new(),
+        }
+    }
+
+    /// Create a new, empty pool with space for `capacity` nodes
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn with_capacity(capacity: usize) -> LinkedListPool<V, K, F>
+    where
+        F: Default,
+    {
+        LinkedListPool {
+            nodes: SlabPool::with_capacity(capacity, 0),
+        }
+    }
+
+    /// Get the underlying pool of nodes
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn nodes(&self) -> &SlabPool<DefaultSlot<Node<V, K>>, K, F> {
+        &self.nodes
+    }
+
+    /// Allocate a new, empty list, returning the key of its head
+    ///
+    /// Panics if the pool is out of keys
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[must_use]
+    pub fn new_list(&mut self) -> K {
+        self.try_new_list().expect("linked list pool out of keys")
+    }
+
+    /// Allocate a new, empty list, returning the key of its head
+    ///
+    /// Returns an error if the pool is out of keys
+    #[cfg_attr(not(tarpaulin), inline)]
+    #[allow(clippy::result_unit_err)]
+    pub fn try_new_list(&mut self) -> Result<K, ()> {
+        let head = self.alloc(None).map_err(|_| ())?;
+        #[cfg(debug_assertions)]
+        {
+            self.nodes[head].list = Some(head);
+        }
+        Ok(head)
+    }
+
+    /// Whether `key` is the head of a list, rather than an element
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn is_list(&self, key: K) -> bool {
+        self.nodes[key].value.is_none()
+    }
+
+    /// Whether the list with head `list` has no elements
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn is_empty(&self, list: K) -> bool {
+        self.nodes[list].next == list
+    }
+
+    /// Get the number of elements in the list with head `list`
+    ///
+    /// Takes time linear in the length of the list
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn len(&self, list: K) -> usize {
+        self.keys(list).count()
+    }
+
+    /// Get the first element of a list, or `None` if it is empty
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn front(&self, list: K) -> Option<K> {
+        self.next(list)
+    }
+
+    /// Get the last element of a list, or `None` if it is empty
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn back(&self, list: K) -> Option<K> {
+        self.prev(list)
+    }
+
+    /// Get the element after `pos`, or `None` if `pos` is the last element of its list
+    ///
+    /// If `pos` is the head of a list, returns its first element. An element which is not in a list has no successor.
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn next(&self, pos: K) -> Option<K> {
+        let next = self.nodes[pos].next;
+        if self.is_list(next) || next == pos {
+            None
+        } else {
+            Some(next)
+        }
+    }
+
+    /// Get the element before `pos`, or `None` if `pos` is the first element of its list
+    ///
+    /// If `pos` is the head of a list, returns its last element. An element which is not in a list has no predecessor.
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn prev(&self, pos: K) -> Option<K> {
+        let prev = self.nodes[pos].prev;
+        if self.is_list(prev) || prev == pos {
+            None
+        } else {
+            Some(prev)
+        }
+    }
+
+    /// Get the value of an element, or `None` if `key` is the head of a list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get(&self, key: K) -> Option<&V> {
+        self.nodes[key].value.as_ref()
+    }
+
+    /// Get the value of an element mutably, or `None` if `key` is the head of a list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get_mut(&mut self, key: K) -> Option<&mut V> {
+        self.nodes[key].value.as_mut()
+    }
+
+    /// Iterate over the keys of the elements of a list, from front to back
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn keys(&self, list: K) -> Keys<'_, V, K, F> {
+        Keys {
+            pool: self,
+            front: self.nodes[list].next,
+            back: self.nodes[list].prev,
+            done: self.is_empty(list),
+        }
+    }
+
+    /// Iterate over the keys and values of the elements of a list, from front to back
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn iter(&self, list: K) -> Iter<'_, V, K, F> {
+        Iter(self.keys(list))
+    }
+
+    /// Link the unlinked element `node` into a list directly after `pos`, which may be an element or the head of a list
+    ///
+    /// Linking directly after the head of a list makes `node` its first element.
+    /// In debug builds, panics if `node` is the head of a list or already belongs to a list.
+    #[cfg_attr(not(tarpaulin), inline)]
+    #[track_caller]
+    pub fn link_after(&mut self, pos: K, node: K) {
+        debug_assert!(!self.is_list(node), "cannot link the head of a list");
+        #[cfg(debug_assertions)]
+        {
+            if let Some(list) = self.nodes[node].list {
+                panic!(
+                    "node {} already belongs to list {}",
+                    node.index(),
+                    list.index()
+                );
+            }
+            self.nodes[node].list = Some(self.owner(pos));
+        }
+        let next = self.nodes[pos].next;
+        self.nodes[node].prev = pos;
+        self.nodes[node].next = next;
+        self.nodes[pos].next = node;
+        self.nodes[next].prev = node;
+    }
+
+    /// Link the unlinked element `node` into a list directly before `pos`, which may be an element or the head of a list
+    ///
+    /// Linking directly before the head of a list makes `node` its last element.
+    /// In debug builds, panics if `node` is the head of a list or already belongs to a list.
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    pub fn link_before(&mut self, pos: K, node: K) {
+        self.link_after(self.nodes[pos].prev, node)
+    }
+
+    /// Unlink an element from the list it belongs to, if any, without removing it from the pool
+    ///
+    /// In debug builds, panics if `node` is the head of a list
+    #[cfg_attr(not(tarpaulin), inline)]
+    #[track_caller]
+    pub fn unlink(&mut self, node: K) {
+        debug_assert!(!self.is_list(node), "cannot unlink the head of a list");
+        let Node { prev, next, .. } = self.nodes[node];
+        self.nodes[prev].next = next;
+        self.nodes[next].prev = prev;
+        self.nodes[node].prev = node;
+        self.nodes[node].next = node;
+        #[cfg(debug_assertions)]
+        {
+            self.nodes[node].list = None;
+        }
+    }
+
+    /// Insert a value as the first element of a list, returning its key
+    ///
+    /// Panics if the pool is out of keys
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    pub fn push_front(&mut self, list: K, value: V) -> K {
+        let node = self.insert(value);
+        self.link_after(list, node);
+        node
+    }
+
+    /// Insert a value as the last element of a list, returning its key
+    ///
+    /// Panics if the pool is out of keys
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    pub fn push_back(&mut self, list: K, value: V) -> K {
+        let node = self.insert(value);
+        self.link_before(list, node);
+        node
+    }
+
+    /// Remove the first element of a list from the pool, returning its value, or `None` if the list is empty
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn pop_front(&mut self, list: K) -> Option<V> {
+        let node = self.front(list)?;
+        Some(self.take(node))
+    }
+
+    /// Remove the last element of a list from the pool, returning its value, or `None` if the list is empty
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn pop_back(&mut self, list: K) -> Option<V> {
+        let node = self.back(list)?;
+        Some(self.take(node))
+    }
+
+    /// Move all elements of the list `src` directly after `pos`, which may be an element or the head of another list, leaving `src` empty
+    ///
+    /// In debug builds, panics if `pos` belongs to `src`
+    #[cfg_attr(not(tarpaulin), inline)]
+    #[track_caller]
+    pub fn splice(&mut self, pos: K, src: K) {
+        debug_assert!(self.is_list(src), "can only splice a list");
+        debug_assert!(self.owner(pos) != src, "cannot splice a list into itself");
+        if !self.is_empty(src) {
+            self.transfer(self.nodes[src].next, self.nodes[src].prev, pos)
+        }
+    }
+
+    /// Move all elements of the list `src` to the back of the list `dst`, leaving `src` empty
+    ///
+    /// In debug builds, panics if `dst` and `src` are the same list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    pub fn append(&mut self, dst: K, src: K) {
+        debug_assert!(self.is_list(dst), "can only append to a list");
+        self.splice(self.nodes[dst].prev, src)
+    }
+
+    /// Split a list in two before the element `at`, returning a new list containing `at` and all elements after it
+    ///
+    /// If `at` is the head of the list, returns a new, empty list. Panics if the pool is out of keys.
+    /// In debug builds, panics if `at` does not belong to `list`.
+    #[cfg_attr(not(tarpaulin), inline)]
+    #[track_caller]
+    pub fn split_off(&mut self, list: K, at: K) -> K {
+        debug_assert!(self.owner(at) == list, "split position not in list");
+        let new = self.new_list();
+        if at != list {
+            self.transfer(at, self.nodes[list].prev, new);
+        }
+        new
+    }
+
+    /// Get a cursor pointing to the first element of a list, or to its head if it is empty
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn cursor_front(&self, list: K) -> Cursor<'_, V, K, F> {
+        Cursor {
+            current: self.nodes[list].next,
+            list,
+            pool: self,
+        }
+    }
+
+    /// Get a cursor pointing to the last element of a list, or to its head if it is empty
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn cursor_back(&self, list: K) -> Cursor<'_, V, K, F> {
+        Cursor {
+            current: self.nodes[list].prev,
+            list,
+            pool: self,
+        }
+    }
+
+    /// Get a cursor pointing to `pos`, which may be an element of `list` or its head
+    ///
+    /// In debug builds, panics if `pos` does not belong to `list`
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    pub fn cursor_at(&self, list: K, pos: K) -> Cursor<'_, V, K, F> {
+        debug_assert!(self.owner(pos) == list, "cursor position not in list");
+        Cursor {
+            current: pos,
+            list,
+            pool: self,
+        }
+    }
+
+    /// Get a mutable cursor pointing to the first element of a list, or to its head if it is empty
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn cursor_front_mut(&mut self, list: K) -> CursorMut<'_, V, K, F> {
+        CursorMut {
+            current: self.nodes[list].next,
+            list,
+            pool: self,
+        }
+    }
+
+    /// Get a mutable cursor pointing to the last element of a list, or to its head if it is empty
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn cursor_back_mut(&mut self, list: K) -> CursorMut<'_, V, K, F> {
+        CursorMut {
+            current: self.nodes[list].prev,
+            list,
+            pool: self,
+        }
+    }
+
+    /// Get a mutable cursor pointing to `pos`, which may be an element of `list` or its head
+    ///
+    /// In debug builds, panics if `pos` does not belong to `list`
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    pub fn cursor_at_mut(&mut self, list: K, pos: K) -> CursorMut<'_, V, K, F> {
+        debug_assert!(self.owner(pos) == list, "cursor position not in list");
+        CursorMut {
+            current: pos,
+            list,
+            pool: self,
+        }
+    }
+
+    /// Allocate a node linked only to itself
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn alloc(&mut self, value: Option<V>) -> Result<K, Option<V>> {
+        let key = self
+            .nodes
+            .try_insert(Node::new(value, K::new_unchecked(0)))
+            .map_err(|node| node.value)?;
+        self.nodes[key].prev = key;
+        self.nodes[key].next = key;
+        Ok(key)
+    }
+
+    /// Move the elements `first..=last` of a list directly after `pos`
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn transfer(&mut self, first: K, last: K, pos: K) {
+        let before = self.nodes[first].prev;
+        let after = self.nodes[last].next;
+        self.nodes[before].next = after;
+        self.nodes[after].prev = before;
+        let next = self.nodes[pos].next;
+        self.nodes[pos].next = first;
+        self.nodes[first].prev = pos;
+        self.nodes[last].next = next;
+        self.nodes[next].prev = last;
+        #[cfg(debug_assertions)]
+        {
+            let list = self.owner(pos);
+            let mut node = first;
+            loop {
+                self.nodes[node].list = Some(list);
+                if node == last {
+                    break;
+                }
+                node = self.nodes[node].next;
+            }
+        }
+    }
+
+    /// Get the head of the list `pos` belongs to, where a list head belongs to itself
+    ///
+    /// Panics if `pos` is an element not in any list
+    #[cfg(debug_assertions)]
+    #[track_caller]
+    fn owner(&self, pos: K) -> K {
+        match self.nodes[pos].list {
+            Some(list) => list,
+            None => panic!("node {} does not belong to a list", pos.index()),
+        }
+    }
+
+    /// Membership is not tracked in release builds
+    #[cfg(not(debug_assertions))]
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn owner(&self, pos: K) -> K {
+        pos
+    }
+}
+
+impl<V, K, F> Insert<K, V> for LinkedListPool<V, K, F>
+where
+    K: ContiguousIx,
+    F: FreeList<[DefaultSlot<Node<V, K>>], K>,
+{
+    /// Insert an element which does not belong to any list
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_insert(&mut self, value: V) -> Result<K, V> {
+        self.alloc(Some(value))
+            .map_err(|value| value.expect("value returned on failure"))
+    }
+}
+
+impl<V, K, F> Pool<K> for LinkedListPool<V, K, F>
+where
+    K: ContiguousIx,
+    F: FreeList<[DefaultSlot<Node<V, K>>], K>,
+{
+    /// Delete an element, unlinking it from its list, or delete a list along with all of its elements
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn delete(&mut self, key: K) {
+        if self.is_list(key) {
+            while let Some(node) = self.front(key) {
+                self.delete(node)
+            }
+        } else {
+            self.unlink(key);
+        }
+        self.nodes.delete(key);
+    }
+}
+
+impl<V, K, F> Take<K, V> for LinkedListPool<V, K, F>
+where
+    K: ContiguousIx,
+    F: FreeList<[DefaultSlot<Node<V, K>>], K>,
+{
+    /// Remove an element from the pool, unlinking it from its list, and return its value
+    ///
+    /// Returns `None`, leaving the pool unchanged, if `key` is the head of a list
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_take(&mut self, key: K) -> Option<V> {
+        if self.is_list(key) {
+            return None;
+        }
+        self.unlink(key);
+        let value = self.nodes[key].value.take();
+        self.nodes.delete(key);
+        value
+    }
+}
+
+impl<V, K, F> GetRef<K, V> for LinkedListPool<V, K, F>
+where
+    K: ContiguousIx,
+    F: FreeList<[DefaultSlot<Node<V, K>>], K>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at(&self, key: K) -> Option<&V> {
+        self.nodes.try_at(key)?.value.as_ref()
+    }
+}
+
+impl<V, K, F> GetMut<K, V> for LinkedListPool<V, K, F>
+where
+    K: ContiguousIx,
+    F: FreeList<[DefaultSlot<Node<V, K>>], K>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at_mut(&mut self, key: K) -> Option<&mut V> {
+        self.nodes.try_at_mut(key)?.value.as_mut()
+    }
+}
+
+/// An iterator over the keys of the elements of a list in a [`LinkedListPool`], created by [`LinkedListPool::keys`]
+#[derive(Debug)]
+pub struct Keys<'a, V, K, F> {
+    pool: &'a LinkedListPool<V, K, F>,
+    front: K,
+    back: K,
+    done: bool,
+}
+
+impl<'a, V, K, F> Clone for Keys<'a, V, K, F>
+where
+    K: Copy,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn clone(&self) -> Self {
+        Keys { ..*self }
+    }
+}
+
+impl<'a, V, K, F> Iterator for Keys<'a, V, K, F>
+where
+    K: ContiguousIx,
+    F: FreeList<[DefaultSlot<Node<V, K>>], K>,
+{
+    type Item = K;
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn next(&mut self) -> Option<K> {
+        if self.done {
+            return None;
+        }
+        let key = self.front;
+        self.done = key == self.back;
+        self.front = self.pool.nodes[key].next;
+        Some(key)
+    }
+}
+
+impl<'a, V, K, F> DoubleEndedIterator for Keys<'a, V, K, F>
+where
+    K: ContiguousIx,
+    F: FreeList<[DefaultSlot<Node<V, K>>], K>,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn next_back(&mut self) -> Option<K> {
+        if self.done {
+            return None;
+        }
+        let key = self.back;
+        self.done = key == self.front;
+        self.back = self.pool.nodes[key].prev;
+        Some(key)
+    }
+}
+
+/// An iterator over the keys and values of the elements of a list in a [`LinkedListPool`], created by [`LinkedListPool::iter`]
+#[derive(Debug)]
+pub struct Iter<'a, V, K, F>(Keys<'a, V, K, F>);
+
+impl<'a, V, K, F> Clone for Iter<'a, V, K, F>
+where
+    K: Copy,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn clone(&self) -> Self {
+        Iter(self.0.clone())
+    }
+}
+
+impl<'a, V, K, F> Iterator for Iter<'a, V, K, F>
+where
+    K: ContiguousIx,
+    F: FreeList<[DefaultSlot<Node<V, K>>], K>,
+{
+    type Item = (K, &'a V);
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.0.next()?;
+        Some((key, self.0.pool.get(key).expect("element of list")))
+    }
+}
+
+impl<'a, V, K, F> DoubleEndedIterator for Iter<'a, V, K, F>
+where
+    K: ContiguousIx,
+    F: FreeList<[DefaultSlot<Node<V, K>>], K>,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let key = self.0.next_back()?;
+        Some((key, self.0.pool.get(key).expect("element of list")))
+    }
+}
+
+/// A cursor over a list in a [`LinkedListPool`]
+///
+/// A cursor points either to an element of its list or to the list's head, which sits between the last and first elements.
+#[derive(Debug)]
+pub struct Cursor<'a, V, K, F> {
+    pool: &'a LinkedListPool<V, K, F>,
+    list: K,
+    current: K,
+}
+
+impl<'a, V, K, F> Clone for Cursor<'a, V, K, F>
+where
+    K: Copy,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn clone(&self) -> Self {
+        Cursor { ..*self }
+    }
+}
+
+impl<'a, V, K, F> Cursor<'a, V, K, F>
+where
+    K: ContiguousIx,
+    F: FreeList<[DefaultSlot<Node<V, K>>], K>,
+{
+    /// Get the head of the list this cursor is over
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn list(&self) -> K {
+        self.list
+    }
+
+    /// Get the element this cursor points to, or `None` if it points to the head of the list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn current(&self) -> Option<K> {
+        if self.current == self.list {
+            None
+        } else {
+            Some(self.current)
+        }
+    }
+
+    /// Get the value of the element this cursor points to, or `None` if it points to the head of the list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get(&self) -> Option<&'a V> {
+        self.pool.get(self.current)
+    }
+
+    /// Get the value of the element after the one this cursor points to, or `None` if there is none
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn peek_next(&self) -> Option<&'a V> {
+        self.pool.get(self.pool.nodes[self.current].next)
+    }
+
+    /// Get the value of the element before the one this cursor points to, or `None` if there is none
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn peek_prev(&self) -> Option<&'a V> {
+        self.pool.get(self.pool.nodes[self.current].prev)
+    }
+
+    /// Move this cursor to the next element, or from the last element to the head of the list, or from the head to the first element
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn move_next(&mut self) {
+        self.current = self.pool.nodes[self.current].next
+    }
+
+    /// Move this cursor to the previous element, or from the first element to the head of the list, or from the head to the last element
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn move_prev(&mut self) {
+        self.current = self.pool.nodes[self.current].prev
+    }
+}
+
+/// A cursor over a list in a [`LinkedListPool`] which can modify the list
+///
+/// A cursor points either to an element of its list or to the list's head, which sits between the last and first elements.
+#[derive(Debug)]
+pub struct CursorMut<'a, V, K, F> {
+    pool: &'a mut LinkedListPool<V, K, F>,
+    list: K,
+    current: K,
+}
+
+impl<'a, V, K, F> CursorMut<'a, V, K, F>
+where
+    K: ContiguousIx,
+    F: FreeList<[DefaultSlot<Node<V, K>>], K>,
+{
+    /// Get a read-only cursor pointing to the same position as this one
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn as_cursor(&self) -> Cursor<'_, V, K, F> {
+        Cursor {
+            pool: self.pool,
+            list: self.list,
+            current: self.current,
+        }
+    }
+
+    /// Get the head of the list this cursor is over
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn list(&self) -> K {
+        self.list
+    }
+
+    /// Get the element this cursor points to, or `None` if it points to the head of the list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn current(&self) -> Option<K> {
+        self.as_cursor().current()
+    }
+
+    /// Get the value of the element this cursor points to, or `None` if it points to the head of the list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get(&self) -> Option<&V> {
+        self.pool.get(self.current)
+    }
+
+    /// Get the value of the element this cursor points to mutably, or `None` if it points to the head of the list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get_mut(&mut self) -> Option<&mut V> {
+        self.pool.get_mut(self.current)
+    }
+
+    /// Move this cursor to the next element, or from the last element to the head of the list, or from the head to the first element
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn move_next(&mut self) {
+        self.current = self.pool.nodes[self.current].next
+    }
+
+    /// Move this cursor to the previous element, or from the first element to the head of the list, or from the head to the last element
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn move_prev(&mut self) {
+        self.current = self.pool.nodes[self.current].prev
+    }
+
+    /// Insert a value after the current position, returning its key
+    ///
+    /// If the cursor points to the head of the list, the value becomes the first element. Panics if the pool is out of keys.
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn insert_after(&mut self, value: V) -> K {
+        let node = self.pool.insert(value);
+        self.pool.link_after(self.current, node);
+        node
+    }
+
+    /// Insert a value before the current position, returning its key
+    ///
+    /// If the cursor points to the head of the list, the value becomes the last element. Panics if the pool is out of keys.
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn insert_before(&mut self, value: V) -> K {
+        let node = self.pool.insert(value);
+        self.pool.link_before(self.current, node);
+        node
+    }
+
+    /// Remove the current element from the pool and return its value, moving the cursor to the next position
+    ///
+    /// Returns `None`, leaving the list unchanged, if the cursor points to the head of the list
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn remove_current(&mut self) -> Option<V> {
+        let node = self.current()?;
+        self.move_next();
+        self.pool.try_take(node)
+    }
+
+    /// Unlink the current element from the list without removing it from the pool, returning its key and moving the cursor to the next position
+    ///
+    /// Returns `None`, leaving the list unchanged, if the cursor points to the head of the list
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn unlink_current(&mut self) -> Option<K> {
+        let node = self.current()?;
+        self.move_next();
+        self.pool.unlink(node);
+        Some(node)
+    }
+
+    /// Move all elements of the list `src` after the current position, leaving `src` empty
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    pub fn splice_after(&mut self, src: K) {
+        self.pool.splice(self.current, src)
+    }
+
+    /// Move all elements of the list `src` before the current position, leaving `src` empty
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    pub fn splice_before(&mut self, src: K) {
+        self.pool.splice(self.pool.nodes[self.current].prev, src)
+    }
+
+    /// Split the list after the current position, returning a new list containing all later elements
+    ///
+    /// If the cursor points to the head of the list, the whole list is moved. Panics if the pool is out of keys.
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn split_after(&mut self) -> K {
+        let next = self.pool.nodes[self.current].next;
+        self.pool.split_off(self.list, next)
+    }
+
+    /// Split the list before the current position, returning a new list containing all earlier elements
+    ///
+    /// If the cursor points to the head of the list, the whole list is moved. Panics if the pool is out of keys.
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn split_before(&mut self) -> K {
+        let new = self.pool.new_list();
+        let first = self.pool.nodes[self.list].next;
+        let last = self.pool.nodes[self.current].prev;
+        if last != self.list {
+            self.pool.transfer(first, last, new);
+        }
+        new
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{Rng, SeedableRng};
+    use rand_xoshiro::Xoshiro256StarStar;
+
+    use super::*;
+
+    type TestPool = LinkedListPool<u32, u32>;
+
+    fn values(pool: &TestPool, list: u32) -> Vec<u32> {
+        let forward: Vec<_> = pool.iter(list).map(|(_, v)| *v).collect();
+        let mut backward: Vec<_> = pool.iter(list).rev().map(|(_, v)| *v).collect();
+        backward.reverse();
+        assert_eq!(forward, backward);
+        assert_eq!(pool.len(list), forward.len());
+        assert_eq!(pool.is_empty(list), forward.is_empty());
+        forward
+    }
+
+    #[test]
+    fn basic_linked_list_usage() {
+        let mut pool = TestPool::new();
+        let a = pool.new_list();
+        let b = pool.new_list();
+        assert!(pool.is_list(a));
+        assert_eq!(values(&pool, a), []);
+        assert_eq!(pool.front(a), None);
+
+        let two = pool.push_back(a, 2);
+        let one = pool.push_front(a, 1);
+        let four = pool.push_back(a, 4);
+        let three = pool.insert(3);
+        assert!(!pool.is_list(three));
+        assert_eq!(pool.next(three), None);
+        pool.link_before(four, three);
+        assert_eq!(values(&pool, a), [1, 2, 3, 4]);
+        assert_eq!(pool.front(a), Some(one));
+        assert_eq!(pool.back(a), Some(four));
+        assert_eq!(pool.next(two), Some(three));
+        assert_eq!(pool.prev(two), Some(one));
+        assert_eq!(pool.prev(one), None);
+        assert_eq!(pool.next(four), None);
+        assert_eq!(
+            pool.keys(a).rev().collect::<Vec<_>>(),
+            [four, three, two, one]
+        );
+
+        pool.unlink(two);
+        pool.link_after(b, two);
+        *pool.at_mut(two) = 20;
+        assert_eq!(values(&pool, a), [1, 3, 4]);
+        assert_eq!(values(&pool, b), [20]);
+
+        pool.append(b, a);
+        assert_eq!(values(&pool, a), []);
+        assert_eq!(values(&pool, b), [20, 1, 3, 4]);
+        pool.splice(a, b);
+        let c = pool.split_off(a, three);
+        assert_eq!(values(&pool, a), [20, 1]);
+        assert_eq!(values(&pool, c), [3, 4]);
+        pool.splice(one, c);
+        assert_eq!(values(&pool, a), [20, 1, 3, 4]);
+        let d = pool.split_off(a, a);
+        assert_eq!(values(&pool, d), []);
+
+        assert_eq!(pool.pop_front(a), Some(20));
+        assert_eq!(pool.pop_back(a), Some(4));
+        assert_eq!(pool.take(one), 1);
+        assert_eq!(pool.try_take(a), None);
+        assert_eq!(pool.try_at(a), None);
+        assert_eq!(values(&pool, a), [3]);
+        pool.delete(a);
+        pool.delete(b);
+        pool.delete(d);
+        pool.delete(c);
+        assert_eq!(pool.nodes().free_slots(), pool.nodes().total_slots());
+    }
+
+    #[test]
+    fn linked_list_cursors() {
+        let mut pool = TestPool::new();
+        let list = pool.new_list();
+        let other = pool.new_list();
+        let mut cursor = pool.cursor_front_mut(list);
+        assert_eq!(cursor.current(), None);
+        cursor.insert_after(3);
+        cursor.insert_before(5);
+        cursor.insert_after(1);
+        cursor.move_next();
+        assert_eq!(cursor.get(), Some(&1));
+        cursor.insert_after(2);
+        *cursor.get_mut().unwrap() = 0;
+        assert_eq!(values(&pool, list), [0, 2, 3, 5]);
+
+        pool.push_back(other, 4);
+        let mut cursor = pool.cursor_back_mut(list);
+        cursor.splice_before(other);
+        assert_eq!(cursor.as_cursor().peek_prev(), Some(&4));
+        cursor.move_prev();
+        cursor.move_prev();
+        assert_eq!(cursor.remove_current(), Some(3));
+        assert_eq!(cursor.get(), Some(&4));
+        let tail = cursor.split_after();
+        let head = cursor.split_before();
+        assert_eq!(values(&pool, head), [0, 2]);
+        assert_eq!(values(&pool, list), [4]);
+        assert_eq!(values(&pool, tail), [5]);
+
+        let mut cursor = pool.cursor_at_mut(list, list);
+        cursor.splice_after(head);
+        cursor.splice_before(tail);
+        cursor.move_next();
+        let zero = cursor.unlink_current().unwrap();
+        assert_eq!(pool.at(zero), &0);
+        assert_eq!(values(&pool, list), [2, 4, 5]);
+
+        let mut cursor = pool.cursor_back(list);
+        assert_eq!(cursor.get(), Some(&5));
+        assert_eq!(cursor.peek_next(), None);
+        cursor.move_next();
+        assert_eq!(cursor.current(), None);
+        cursor.move_next();
+        assert_eq!(cursor.get(), Some(&2));
+        assert_eq!(cursor.list(), list);
+        let cursor = pool.cursor_front(list);
+        assert_eq!(cursor.peek_next(), Some(&4));
+        assert_eq!(cursor.peek_prev(), None);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "already belongs to list")]
+    fn double_link() {
+        let mut pool = TestPool::new();
+        let a = pool.new_list();
+        let b = pool.new_list();
+        let node = pool.push_back(a, 0);
+        pool.link_after(b, node);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "cursor position not in list")]
+    fn cursor_in_wrong_list() {
+        let mut pool = TestPool::new();
+        let a = pool.new_list();
+        let b = pool.new_list();
+        let node = pool.push_back(a, 0);
+        let _ = pool.cursor_at(b, node);
+    }
+
+    #[test]
+    fn linked_list_stress() {
+        let mut rng = Xoshiro256StarStar::from_seed([7; 32]);
+        let mut pool = TestPool::new();
+        let mut lists: Vec<(u32, Vec<(u32, u32)>)> = Vec::new();
+        for i in 0..2000 {
+            if lists.len() < 2 || rng.gen_range(0..20) == 0 {
+                lists.push((pool.new_list(), Vec::new()));
+                continue;
+            }
+            let l = rng.gen_range(0..lists.len());
+            match rng.gen_range(0..6) {
+                0 | 1 => {
+                    let node = pool.push_back(lists[l].0, i);
+                    lists[l].1.push((node, i));
+                }
+                2 => {
+                    let node = pool.push_front(lists[l].0, i);
+                    lists[l].1.insert(0, (node, i));
+                }
+                3 if !lists[l].1.is_empty() => {
+                    let at = rng.gen_range(0..lists[l].1.len());
+                    let (node, value) = lists[l].1.remove(at);
+                    assert_eq!(pool.take(node), value);
+                }
+                4 if !lists[l].1.is_empty() => {
+                    let at = rng.gen_range(0..lists[l].1.len());
+                    let list = pool.split_off(lists[l].0, lists[l].1[at].0);
+                    let split = lists[l].1.split_off(at);
+                    lists.push((list, split));
+                }
+                _ => {
+                    let m = rng.gen_range(0..lists.len());
+                    if l != m {
+                        pool.append(lists[l].0, lists[m].0);
+                        let moved = std::mem::take(&mut lists[m].1);
+                        lists[l].1.extend(moved);
+                    }
+                }
+            }
+        }
+        for (list, model) in lists {
+            assert!(pool.keys(list).eq(model.iter().map(|(k, _)| *k)));
+            assert!(pool
+                .iter(list)
+                .rev()
+                .map(|(_, v)| *v)
+                .eq(model.iter().rev().map(|(_, v)| *v)));
+            pool.delete(list);
+        }
+        assert_eq!(pool.nodes().free_slots(), pool.nodes().total_slots());
+    }
+}
@@ -2,13 +2,19 @@
 Traits for map-like containers
 */
 
-use std::collections::VecDeque;
+use std::{
+    borrow::Borrow,
+    collections::{BTreeMap, HashMap, VecDeque},
+    hash::{BuildHasher, Hash},
+};
 
 use crate::{
     index::ContiguousIx,
     pool::{GetMut, GetRef, PoolMut, PoolRef},
 };
 
+use super::{Container, ContainerPool, HasLen, IsEmpty};
+
 /// Given a key `K` an index `I`, get a reference to the associated value `V`
 pub trait GetIndex<K, I, V> {
     #[must_use]
@@ -63,6 +69,392 @@ where
     }
 }
 
+/// A [`Pool`](super::Pool) allocating maps from indices of type `I` to values of type `V`
+pub trait MapPool<K, I, V>: ContainerPool<K, Elem = (I, V)> {
+    /// An iterator over the entries of a map in this pool
+    type Iter<'a>: Iterator<Item = (&'a I, &'a V)>
+    where
+        Self: 'a,
+        I: 'a,
+        V: 'a;
+
+    /// Insert an entry into a map, returning the value previously associated with `index`, if any
+    ///
+    /// Leaves the pool in an unspecified state and returns an unspecified value or panics if used on an unrecognized key
+    fn insert_entry(&mut self, key: K, index: I, value: V) -> Option<V>;
+
+    /// Remove an entry from a map, returning its value if it was present
+    ///
+    /// Leaves the pool in an unspecified state and returns an unspecified value or panics if used on an unrecognized key
+    fn remove_entry(&mut self, key: K, index: &I) -> Option<V>;
+
+    /// Get the value associated with `index` in a map
+    ///
+    /// Returns an unspecified value or panics if used on an unrecognized key
+    #[must_use]
+    fn get_entry(&self, key: K, index: &I) -> Option<&V>;
+
+    /// Get the value associated with `index` in a map mutably
+    ///
+    /// Returns an unspecified value or panics if used on an unrecognized key
+    #[must_use]
+    fn get_entry_mut(&mut self, key: K, index: &I) -> Option<&mut V>;
+
+    /// Iterate over the entries of a map
+    ///
+    /// Returns an unspecified iterator or panics if used on an unrecognized key
+    #[must_use]
+    fn iter_entries(&self, key: K) -> Self::Iter<'_>;
+}
+
+/// A trait implemented by things which can be used as maps from indices of type `I` to values of type `V`
+pub trait MapLike<I, V>: Container<Elem = (I, V)> + Default {
+    /// An iterator over the entries of this map
+    type Iter<'a>: Iterator<Item = (&'a I, &'a V)>
+    where
+        Self: 'a,
+        I: 'a,
+        V: 'a;
+
+    /// Insert an entry into this map, returning the value previously associated with `index`, if any
+    fn insert_map(&mut self, index: I, value: V) -> Option<V>;
+
+    /// Remove an entry from this map, returning its value if it was present
+    fn remove_map(&mut self, index: &I) -> Option<V>;
+
+    /// Get the value associated with `index` in this map
+    fn get_map(&self, index: &I) -> Option<&V>;
+
+    /// Get the value associated with `index` in this map mutably
+    fn get_map_mut(&mut self, index: &I) -> Option<&mut V>;
+
+    /// Iterate over the entries of this map
+    fn iter_map(&self) -> Self::Iter<'_>;
+}
+
+impl<P, K, I, V> MapPool<K, I, V> for P
+where
+    P: PoolMut<K> + PoolRef<K>,
+    P::Object: MapLike<I, V> + 'static, //TODO: relax this?
+    I: 'static,
+    V: 'static,
+{
+    type Iter<'a>
+        = <P::Object as MapLike<I, V>>::Iter<'a>
+    where
+        Self: 'a,
+        I: 'a,
+        V: 'a;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_entry(&mut self, key: K, index: I, value: V) -> Option<V> {
+        self.at_mut(key).insert_map(index, value)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn remove_entry(&mut self, key: K, index: &I) -> Option<V> {
+        self.at_mut(key).remove_map(index)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn get_entry(&self, key: K, index: &I) -> Option<&V> {
+        self.at(key).get_map(index)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn get_entry_mut(&mut self, key: K, index: &I) -> Option<&mut V> {
+        self.at_mut(key).get_map_mut(index)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn iter_entries(&self, key: K) -> Self::Iter<'_> {
+        self.at(key).iter_map()
+    }
+}
+
+/// An iterator over the entries of a [`VecMap`]
+pub type VecMapIter<'a, I, V> =
+    std::iter::Map<std::slice::Iter<'a, (I, V)>, fn(&'a (I, V)) -> (&'a I, &'a V)>;
+
+/// A map from indices of type `I` to values of type `V`, represented as a vector of entries sorted by index
+///
+/// Lookups take logarithmic time, while insertions and removals take time linear in the size of the map; for small maps, this is often
+/// faster and always more compact than a [`BTreeMap`] or [`HashMap`].
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct VecMap<I, V> {
+    entries: Vec<(I, V)>,
+}
+
+impl<I, V> Default for VecMap<I, V> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<I, V> VecMap<I, V> {
+    /// Create a new, empty map
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new() -> VecMap<I, V> {
+        VecMap {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Create a new, empty map with space for `capacity` entries
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn with_capacity(capacity: usize) -> VecMap<I, V> {
+        VecMap {
+            entries: Vec::with_capacity(capacity),
+        }
+    }
+
+    /// Get the number of entries in this map
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether this map has no entries
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Get the entries of this map, sorted by index
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn as_slice(&self) -> &[(I, V)] {
+        &self.entries
+    }
+
+    /// Remove all entries from this map
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn clear(&mut self) {
+        self.entries.clear()
+    }
+
+    /// Iterate over the entries of this map, in increasing order of index
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn iter(&self) -> VecMapIter<'_, I, V> {
+        self.entries.iter().map(|(i, v)| (i, v))
+    }
+
+    /// Get the position of the entry with the given index, or where it would be inserted
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn search<Q>(&self, index: &Q) -> Result<usize, usize>
+    where
+        I: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.entries
+            .binary_search_by(|(i, _)| i.borrow().cmp(index))
+    }
+
+    /// Get the value associated with `index`
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn get<Q>(&self, index: &Q) -> Option<&V>
+    where
+        I: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let ix = self.search(index).ok()?;
+        Some(&self.entries[ix].1)
+    }
+
+    /// Get the value associated with `index` mutably
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn get_mut<Q>(&mut self, index: &Q) -> Option<&mut V>
+    where
+        I: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let ix = self.search(index).ok()?;
+        Some(&mut self.entries[ix].1)
+    }
+
+    /// Whether this map has an entry with the given index
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn contains_key<Q>(&self, index: &Q) -> bool
+    where
+        I: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        self.search(index).is_ok()
+    }
+
+    /// Insert an entry into this map, returning the value previously associated with `index`, if any
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn insert(&mut self, index: I, value: V) -> Option<V>
+    where
+        I: Ord,
+    {
+        match self.search(&index) {
+            Ok(ix) => Some(std::mem::replace(&mut self.entries[ix].1, value)),
+            Err(ix) => {
+                self.entries.insert(ix, (index, value));
+                None
+            }
+        }
+    }
+
+    /// Remove an entry from this map, returning its value if it was present
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn remove<Q>(&mut self, index: &Q) -> Option<V>
+    where
+        I: Borrow<Q>,
+        Q: Ord + ?Sized,
+    {
+        let ix = self.search(index).ok()?;
+        Some(self.entries.remove(ix).1)
+    }
+}
+
+impl<I, V> FromIterator<(I, V)> for VecMap<I, V>
+where
+    I: Ord,
+{
+    /// Collect entries into a map; if an index appears more than once, its last value is kept
+    fn from_iter<T: IntoIterator<Item = (I, V)>>(iter: T) -> Self {
+        let mut entries: Vec<_> = iter.into_iter().collect();
+        // A stable sort keeps entries with equal indices in order, so the last one can be kept
+        entries.sort_by(|(l, _), (r, _)| l.cmp(r));
+        entries.reverse();
+        entries.dedup_by(|(l, _), (r, _)| l == r);
+        entries.reverse();
+        VecMap { entries }
+    }
+}
+
+impl<I, V> Container for VecMap<I, V> {
+    type Elem = (I, V);
+}
+
+impl<I, V> IsEmpty for VecMap<I, V> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<I, V> HasLen for VecMap<I, V> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<I, V> MapLike<I, V> for VecMap<I, V>
+where
+    I: Ord,
+{
+    type Iter<'a>
+        = VecMapIter<'a, I, V>
+    where
+        Self: 'a,
+        I: 'a,
+        V: 'a;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_map(&mut self, index: I, value: V) -> Option<V> {
+        self.insert(index, value)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn remove_map(&mut self, index: &I) -> Option<V> {
+        self.remove(index)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn get_map(&self, index: &I) -> Option<&V> {
+        self.get(index)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn get_map_mut(&mut self, index: &I) -> Option<&mut V> {
+        self.get_mut(index)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn iter_map(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+}
+
+impl<I, V> MapLike<I, V> for BTreeMap<I, V>
+where
+    I: Ord,
+{
+    type Iter<'a>
+        = std::collections::btree_map::Iter<'a, I, V>
+    where
+        Self: 'a,
+        I: 'a,
+        V: 'a;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_map(&mut self, index: I, value: V) -> Option<V> {
+        self.insert(index, value)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn remove_map(&mut self, index: &I) -> Option<V> {
+        self.remove(index)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn get_map(&self, index: &I) -> Option<&V> {
+        self.get(index)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn get_map_mut(&mut self, index: &I) -> Option<&mut V> {
+        self.get_mut(index)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn iter_map(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+}
+
+impl<I, V, S> MapLike<I, V> for HashMap<I, V, S>
+where
+    I: Hash + Eq,
+    S: BuildHasher + Default,
+{
+    type Iter<'a>
+        = std::collections::hash_map::Iter<'a, I, V>
+    where
+        Self: 'a,
+        I: 'a,
+        V: 'a;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_map(&mut self, index: I, value: V) -> Option<V> {
+        self.insert(index, value)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn remove_map(&mut self, index: &I) -> Option<V> {
+        self.remove(index)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn get_map(&self, index: &I) -> Option<&V> {
+        self.get(index)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn get_map_mut(&mut self, index: &I) -> Option<&mut V> {
+        self.get_mut(index)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn iter_map(&self) -> Self::Iter<'_> {
+        self.iter()
+    }
+}
+
 impl<K, V> GetRef<K, V> for [V]
 where
     K: ContiguousIx,
@@ -223,3 +615,55 @@ where
         self.make_mut().get_mut(key.index())
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{
+        pool::{container::LenPool, slab::SlabPool, Insert},
+        slot::DefaultSlot,
+    };
+
+    fn map_pool_test<M>()
+    where
+        M: MapLike<u32, char> + HasLen + 'static,
+    {
+        let mut pool: SlabPool<DefaultSlot<M>, u8> = SlabPool::new();
+        let a: u8 = pool.insert(M::default());
+        let b: u8 = pool.insert(M::default());
+        assert_eq!(pool.insert_entry(a, 3, 'c'), None);
+        assert_eq!(pool.insert_entry(a, 1, 'a'), None);
+        assert_eq!(pool.insert_entry(a, 3, 'C'), Some('c'));
+        assert_eq!(pool.insert_entry(b, 2, 'b'), None);
+        assert_eq!(pool.get_entry(a, &3), Some(&'C'));
+        assert_eq!(pool.get_entry(a, &2), None);
+        *pool.get_entry_mut(b, &2).unwrap() = 'B';
+        assert_eq!(pool.get_entry_mut(b, &1), None);
+        let mut entries: Vec<_> = pool.iter_entries(a).map(|(i, v)| (*i, *v)).collect();
+        entries.sort();
+        assert_eq!(entries, [(1, 'a'), (3, 'C')]);
+        assert_eq!(pool.key_len(a), 2);
+        assert_eq!(pool.remove_entry(a, &1), Some('a'));
+        assert_eq!(pool.remove_entry(a, &1), None);
+        assert_eq!(pool.key_len(a), 1);
+        assert!(pool.iter_entries(b).eq([(&2, &'B')]));
+    }
+
+    #[test]
+    fn map_pools() {
+        map_pool_test::<VecMap<u32, char>>();
+        map_pool_test::<BTreeMap<u32, char>>();
+        map_pool_test::<HashMap<u32, char>>();
+    }
+
+    #[test]
+    fn vec_map_from_iter() {
+        let map: VecMap<u32, char> = [(3, 'a'), (1, 'b'), (3, 'c'), (2, 'd')]
+            .into_iter()
+            .collect();
+        assert_eq!(map.as_slice(), [(1, 'b'), (2, 'd'), (3, 'c')]);
+        assert!(map.contains_key(&2));
+        assert!(!map.contains_key(&4));
+        assert!(map.iter().map(|(i, _)| *i).eq([1, 2, 3]));
+    }
+}
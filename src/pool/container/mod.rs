@@ -1,17 +1,17 @@
 /*!
 Traits for container allocators
 */
-use std::collections::VecDeque;
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
 
 use super::*;
 
 pub mod array;
+pub mod deque;
+pub mod iter;
+pub mod list;
 pub mod map;
+pub mod set;
 pub mod stack;
-//TODO: deque
-//TODO: set
-//TODO: iter
-//TODO: list
 
 /// A [`Pool`] allocating containers of `Self::Elem`
 pub trait ContainerPool<K>: Pool<K> {
@@ -167,6 +167,18 @@ impl<V> Container for VecDeque<V> {
     type Elem = V;
 }
 
+impl<V> Container for BTreeSet<V> {
+    type Elem = V;
+}
+
+impl<I, V> Container for BTreeMap<I, V> {
+    type Elem = (I, V);
+}
+
+impl<I, V, S> Container for HashMap<I, V, S> {
+    type Elem = (I, V);
+}
+
 #[cfg(feature = "smallvec")]
 impl<A: smallvec::Array> Container for smallvec::SmallVec<A> {
     type Elem = A::Item;
@@ -240,6 +252,27 @@ impl<V> IsEmpty for VecDeque<V> {
     }
 }
 
+impl<V> IsEmpty for BTreeSet<V> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<I, V> IsEmpty for BTreeMap<I, V> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<I, V, S> IsEmpty for HashMap<I, V, S> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+}
+
 #[cfg(feature = "smallvec")]
 impl<A: smallvec::Array> IsEmpty for smallvec::SmallVec<A> {
     fn is_empty(&self) -> bool {
@@ -290,6 +323,27 @@ impl<V> HasLen for VecDeque<V> {
     }
 }
 
+impl<V> HasLen for BTreeSet<V> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<I, V> HasLen for BTreeMap<I, V> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<I, V, S> HasLen for HashMap<I, V, S> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
 #[cfg(feature = "smallvec")]
 impl<A: smallvec::Array> HasLen for smallvec::SmallVec<A> {
     #[cfg_attr(not(tarpaulin), inline(always))]
@@ -0,0 +1,424 @@
+/*!
+Traits for containers implementing sets
+*/
+
+use std::{
+    collections::BTreeSet,
+    hash::{Hash, Hasher},
+    marker::PhantomData,
+};
+
+use crate::index::ContiguousIx;
+
+use super::*;
+
+/// A [`Pool`] allocating sets containing elements of type `Self::Elem`
+pub trait SetPool<K>: ContainerPool<K> {
+    /// Insert an element into a set, returning whether it was not already present
+    ///
+    /// Leaves the pool in an unspecified state and returns an unspecified value or panics if used on an unrecognized key
+    fn insert_elem(&mut self, key: K, elem: Self::Elem) -> bool;
+
+    /// Remove an element from a set, returning whether it was present
+    ///
+    /// Leaves the pool in an unspecified state and returns an unspecified value or panics if used on an unrecognized key
+    fn remove_elem(&mut self, key: K, elem: &Self::Elem) -> bool;
+
+    /// Check whether a set contains an element
+    ///
+    /// Returns an unspecified value or panics if used on an unrecognized key
+    #[must_use]
+    fn contains_elem(&self, key: K, elem: &Self::Elem) -> bool;
+
+    /// Insert every element of the set `src` into the set `dst`, returning whether `dst` changed
+    ///
+    /// `src` and `dst` may be the same key, in which case nothing changes.
+    ///
+    /// Leaves the pool in an unspecified state and returns an unspecified value or panics if used on an unrecognized key
+    fn union_into(&mut self, dst: K, src: K) -> bool;
+}
+
+/// A trait implemented by things which can be used as sets
+pub trait SetLike: Container + Default {
+    /// Insert an element into this set, returning whether it was not already present
+    fn insert_set(&mut self, elem: Self::Elem) -> bool;
+
+    /// Remove an element from this set, returning whether it was present
+    fn remove_set(&mut self, elem: &Self::Elem) -> bool;
+
+    /// Check whether this set contains an element
+    fn contains_set(&self, elem: &Self::Elem) -> bool;
+
+    /// Insert every element of `other` into this set, returning whether this set changed
+    fn union_set(&mut self, other: &Self) -> bool;
+}
+
+impl<P, K> SetPool<K> for P
+where
+    P: PoolMut<K> + PoolRef<K>,
+    K: Clone,
+    P::Object: SetLike,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_elem(&mut self, key: K, elem: Self::Elem) -> bool {
+        self.at_mut(key).insert_set(elem)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn remove_elem(&mut self, key: K, elem: &Self::Elem) -> bool {
+        self.at_mut(key).remove_set(elem)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn contains_elem(&self, key: K, elem: &Self::Elem) -> bool {
+        self.at(key).contains_set(elem)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn union_into(&mut self, dst: K, src: K) -> bool {
+        // Temporarily take `dst` out of the pool so `src` can be borrowed alongside it; if they are the same key, `src` is then empty
+        let mut set = std::mem::take(self.at_mut(dst.clone()));
+        let changed = set.union_set(self.at(src));
+        *self.at_mut(dst) = set;
+        changed
+    }
+}
+
+impl<V> SetLike for BTreeSet<V>
+where
+    V: Ord + Clone,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_set(&mut self, elem: V) -> bool {
+        self.insert(elem)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn remove_set(&mut self, elem: &V) -> bool {
+        self.remove(elem)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn contains_set(&self, elem: &V) -> bool {
+        self.contains(elem)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn union_set(&mut self, other: &Self) -> bool {
+        let len = self.len();
+        self.extend(other.iter().cloned());
+        self.len() != len
+    }
+}
+
+/// Implement [`SetLike`] for a vector type, which is treated as a sorted set without duplicates
+macro_rules! sorted_set_like {
+    ($ty:ty, $elem:ty, $($gen:tt)*) => {
+        impl<$($gen)*> SetLike for $ty
+        where
+            $elem: Ord + Clone,
+        {
+            #[cfg_attr(not(tarpaulin), inline)]
+            fn insert_set(&mut self, elem: $elem) -> bool {
+                match self.binary_search(&elem) {
+                    Ok(_) => false,
+                    Err(ix) => {
+                        self.insert(ix, elem);
+                        true
+                    }
+                }
+            }
+
+            #[cfg_attr(not(tarpaulin), inline)]
+            fn remove_set(&mut self, elem: &$elem) -> bool {
+                match self.binary_search(elem) {
+                    Ok(ix) => {
+                        self.remove(ix);
+                        true
+                    }
+                    Err(_) => false,
+                }
+            }
+
+            #[cfg_attr(not(tarpaulin), inline(always))]
+            fn contains_set(&self, elem: &$elem) -> bool {
+                self.binary_search(elem).is_ok()
+            }
+
+            fn union_set(&mut self, other: &Self) -> bool {
+                let mut changed = false;
+                let mut start = 0;
+                // Since both sets are sorted, each element of `other` is searched for only after the position of the previous one
+                for elem in other.iter() {
+                    match self[start..].binary_search(elem) {
+                        Ok(ix) => start += ix + 1,
+                        Err(ix) => {
+                            self.insert(start + ix, elem.clone());
+                            start += ix + 1;
+                            changed = true;
+                        }
+                    }
+                }
+                changed
+            }
+        }
+    };
+}
+
+sorted_set_like!(Vec<V>, V, V);
+#[cfg(feature = "smallvec")]
+sorted_set_like!(smallvec::SmallVec<A>, A::Item, A: smallvec::Array);
+
+/// A set of indices of type `K`, represented as a bitset
+#[derive(Debug, Clone)]
+pub struct BitSet<K = usize> {
+    words: Vec<u64>,
+    key_type: PhantomData<K>,
+}
+
+impl<K> Default for BitSet<K> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> PartialEq for BitSet<K> {
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn eq(&self, other: &Self) -> bool {
+        self.trimmed() == other.trimmed()
+    }
+}
+
+impl<K> Eq for BitSet<K> {}
+
+impl<K> Hash for BitSet<K> {
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.trimmed().hash(state)
+    }
+}
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+impl<K> BitSet<K> {
+    /// Create a new, empty set
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new() -> BitSet<K> {
+        BitSet {
+            words: Vec::new(),
+            key_type: PhantomData,
+        }
+    }
+
+    /// Get the number of elements in this set
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn len(&self) -> usize {
+        self.words
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    /// Check whether this set is empty
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn is_empty(&self) -> bool {
+        self.words.iter().all(|word| *word == 0)
+    }
+
+    /// Get the words of this bitset, without trailing zero words
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn trimmed(&self) -> &[u64] {
+        let len = self
+            .words
+            .iter()
+            .rposition(|word| *word != 0)
+            .map_or(0, |last| last + 1);
+        &self.words[..len]
+    }
+
+    /// Remove every element from this set
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn clear(&mut self) {
+        self.words.clear()
+    }
+
+    /// Insert every element of `other` into this set, returning whether this set changed
+    pub fn union_with(&mut self, other: &BitSet<K>) -> bool {
+        if other.words.len() > self.words.len() {
+            self.words.resize(other.words.len(), 0);
+        }
+        let mut changed = false;
+        for (word, other) in self.words.iter_mut().zip(other.words.iter()) {
+            changed |= *other & !*word != 0;
+            *word |= *other;
+        }
+        changed
+    }
+}
+
+impl<K: ContiguousIx> BitSet<K> {
+    /// Insert an element into this set, returning whether it was not already present
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn insert(&mut self, elem: K) -> bool {
+        let ix = elem.index();
+        let w = ix / WORD_BITS;
+        if w >= self.words.len() {
+            self.words.resize(w + 1, 0);
+        }
+        let bit = 1 << (ix % WORD_BITS);
+        let absent = self.words[w] & bit == 0;
+        self.words[w] |= bit;
+        absent
+    }
+
+    /// Remove an element from this set, returning whether it was present
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn remove(&mut self, elem: K) -> bool {
+        let ix = elem.index();
+        let Some(word) = self.words.get_mut(ix / WORD_BITS) else {
+            return false;
+        };
+        let bit = 1 << (ix % WORD_BITS);
+        let present = *word & bit != 0;
+        *word &= !bit;
+        present
+    }
+
+    /// Check whether this set contains an element
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn contains(&self, elem: K) -> bool {
+        let ix = elem.index();
+        self.words
+            .get(ix / WORD_BITS)
+            .is_some_and(|word| word & (1 << (ix % WORD_BITS)) != 0)
+    }
+
+    /// Iterate over the elements of this set in increasing order
+    pub fn iter(&self) -> impl Iterator<Item = K> + '_ {
+        self.words.iter().enumerate().flat_map(|(w, word)| {
+            let mut word = *word;
+            std::iter::from_fn(move || {
+                if word == 0 {
+                    return None;
+                }
+                let bit = word.trailing_zeros() as usize;
+                word &= word - 1;
+                Some(K::new_unchecked(w * WORD_BITS + bit))
+            })
+        })
+    }
+}
+
+impl<K> Container for BitSet<K> {
+    type Elem = K;
+}
+
+impl<K> IsEmpty for BitSet<K> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn is_empty(&self) -> bool {
+        self.is_empty()
+    }
+}
+
+impl<K> HasLen for BitSet<K> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn len(&self) -> usize {
+        self.len()
+    }
+}
+
+impl<K: ContiguousIx> SetLike for BitSet<K> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_set(&mut self, elem: K) -> bool {
+        self.insert(elem)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn remove_set(&mut self, elem: &K) -> bool {
+        self.remove(*elem)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn contains_set(&self, elem: &K) -> bool {
+        self.contains(*elem)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn union_set(&mut self, other: &Self) -> bool {
+        self.union_with(other)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{pool::slab::SlabPool, slot::DefaultSlot};
+
+    fn set_pool_test<S>()
+    where
+        S: SetLike<Elem = u32> + HasLen,
+    {
+        let mut pool: SlabPool<DefaultSlot<S>, u32> = SlabPool::new();
+        let a = pool.insert_empty();
+        let b = pool.insert_empty();
+        assert!(pool.insert_elem(a, 5));
+        assert!(pool.insert_elem(a, 1));
+        assert!(!pool.insert_elem(a, 5));
+        assert!(pool.insert_elem(a, 70));
+        assert!(pool.contains_elem(a, &70));
+        assert!(!pool.contains_elem(a, &3));
+        assert!(!pool.contains_elem(b, &5));
+        assert_eq!(pool.key_len(a), 3);
+
+        assert!(pool.insert_elem(b, 3));
+        assert!(pool.insert_elem(b, 5));
+        assert!(pool.insert_elem(b, 100));
+        assert!(pool.union_into(a, b));
+        assert!(!pool.union_into(a, b));
+        assert!(!pool.union_into(a, a));
+        assert_eq!(pool.key_len(a), 5);
+        assert_eq!(pool.key_len(b), 3);
+        for elem in [1, 3, 5, 70, 100] {
+            assert!(pool.contains_elem(a, &elem));
+        }
+
+        assert!(pool.remove_elem(a, &5));
+        assert!(!pool.remove_elem(a, &5));
+        assert!(!pool.remove_elem(a, &1000));
+        assert!(!pool.contains_elem(a, &5));
+        assert!(pool.contains_elem(b, &5));
+        assert_eq!(pool.key_len(a), 4);
+        assert!(pool.union_into(a, b));
+    }
+
+    #[test]
+    fn set_pools() {
+        set_pool_test::<Vec<u32>>();
+        #[cfg(feature = "smallvec")]
+        set_pool_test::<smallvec::SmallVec<[u32; 4]>>();
+        set_pool_test::<BTreeSet<u32>>();
+        set_pool_test::<BitSet<u32>>();
+    }
+
+    #[test]
+    fn bit_set_iter() {
+        let mut set: BitSet<u16> = BitSet::new();
+        assert!(set.is_empty());
+        for elem in [200, 3, 64, 63, 0] {
+            set.insert(elem);
+        }
+        assert_eq!(set.iter().collect::<Vec<_>>(), [0, 3, 63, 64, 200]);
+        assert!(set.remove(200));
+        assert_eq!(set.len(), 4);
+        let mut other = BitSet::new();
+        for elem in [64, 0, 3, 63] {
+            other.insert(elem);
+        }
+        assert_eq!(set, other);
+        set.clear();
+        assert!(set.is_empty());
+        assert_eq!(set, BitSet::new());
+        assert!(!set.remove(3));
+    }
+}
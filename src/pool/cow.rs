@@ -0,0 +1,441 @@
+/*!
+A copy-on-write pool, storing slots in reference-counted chunks so that it can be cloned cheaply
+*/
+use std::{
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+};
+
+use ecow::EcoVec;
+
+use crate::{
+    index::ContiguousIx,
+    slot::{InitFrom, KeySlot, SlotMut, SlotRef},
+};
+
+use super::{
+    container::{array::InsertFromSlice, Container, InsertEmpty, InsertWithCapacity, WithCapacity},
+    GetMut, GetRef, Insert, ObjectPool, Pool, Take,
+};
+
+/// A copy-on-write slab allocator, storing slots of type `S` in reference-counted chunks of `N` slots each
+///
+/// Cloning a `CowPool` takes time linear in the number of chunks rather than the number of slots, as the chunks themselves are shared.
+/// Any mutation, whether by `insert`, `delete`, `take` or `at_mut`, copies only the chunks it touches, and only if they are shared.
+///
+/// Free'd slots are kept in an intrusive free list, and so `S` must be able to store a key, e.g. [`Either<K, V>`](either::Either).
+///
+/// # Notes
+///
+/// As for a [`SlabPool`](super::slab::SlabPool), the implementation of comparison will consider any two pools constructed by the same sequence of
+/// `insert` and `remove`/`delete` operations equivalent, but may consider two pools which map the same keys to the same values but were constructed by a
+/// different sequence of operations to be disequal.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct CowPool<S, K = usize, const N: usize = 64> {
+    chunks: Vec<EcoVec<S>>,
+    free_head: usize,
+    free_slots: usize,
+    key_type: PhantomData<K>,
+}
+
+impl<S, K, const N: usize> Default for CowPool<S, K, N> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S, K, const N: usize> CowPool<S, K, N> {
+    /// Create a new, empty pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new() -> CowPool<S, K, N> {
+        assert!(N > 0, "chunks must contain at least one slot");
+        CowPool {
+            chunks: Vec::new(),
+            free_head: usize::MAX,
+            free_slots: 0,
+            key_type: PhantomData,
+        }
+    }
+
+    /// Get the total number of slots in this pool
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn total_slots(&self) -> usize {
+        self.chunks
+            .last()
+            .map_or(0, |last| (self.chunks.len() - 1) * N + last.len())
+    }
+
+    /// Get the number of free slots in this pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn free_slots(&self) -> usize {
+        self.free_slots
+    }
+
+    /// Get the number of chunks in this pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn chunks(&self) -> usize {
+        self.chunks.len()
+    }
+
+    /// Get the number of chunks in this pool which are currently shared with another pool, and hence would be copied on write
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn shared_chunks(&mut self) -> usize {
+        self.chunks
+            .iter_mut()
+            .map(EcoVec::is_unique)
+            .filter(|unique| !unique)
+            .count()
+    }
+
+    /// Clear this pool, releasing this pool's reference to all of its chunks
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn clear(&mut self) {
+        self.chunks.clear();
+        self.free_head = usize::MAX;
+        self.free_slots = 0;
+    }
+
+    /// Get a reference to a given slot
+    ///
+    /// Note this may expose unstable internal details of the pool data structure when used on a key which has been deleted.
+    ///
+    /// Returns `None` if `key` is invalid
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get_slot(&self, key: K) -> Option<&S>
+    where
+        K: ContiguousIx,
+    {
+        let ix = key.index();
+        self.chunks.get(ix / N)?.get(ix % N)
+    }
+
+    /// Get a mutable reference to a given slot, copying its chunk if it is shared
+    ///
+    /// Note this may expose unstable internal details of the pool data structure when used on a key which has been deleted.
+    /// Modifying the slot corresponding to a deleted key leaves the pool in an invalid state, though this will never cause UB.
+    ///
+    /// Returns `None` if `key` is invalid
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get_slot_mut(&mut self, key: K) -> Option<&mut S>
+    where
+        S: Clone,
+        K: ContiguousIx,
+    {
+        self.slot_mut(key.index())
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn slot_mut(&mut self, ix: usize) -> Option<&mut S>
+    where
+        S: Clone,
+    {
+        self.chunks.get_mut(ix / N)?.make_mut().get_mut(ix % N)
+    }
+
+    /// Allocate a free slot, if any, returning its index
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn alloc(&mut self) -> Option<(K, &mut S)>
+    where
+        S: KeySlot<K> + Clone,
+        K: ContiguousIx,
+    {
+        let ix = self.free_head;
+        let slot = self.chunks.get_mut(ix / N)?.make_mut().get_mut(ix % N)?;
+        let next = slot.key().index();
+        self.free_head = if next == ix { usize::MAX } else { next };
+        self.free_slots -= 1;
+        Some((K::new_unchecked(ix), slot))
+    }
+
+    /// Append a new slot to the end of this pool, returning its key
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_push(&mut self, slot: impl FnOnce() -> S) -> Option<K>
+    where
+        S: Clone,
+        K: ContiguousIx,
+    {
+        let key = K::try_new(self.total_slots())?;
+        match self.chunks.last_mut() {
+            Some(last) if last.len() < N => last.push(slot()),
+            _ => {
+                let mut chunk = EcoVec::with_capacity(N);
+                chunk.push(slot());
+                self.chunks.push(chunk);
+            }
+        }
+        Some(key)
+    }
+
+    /// Get the key at the head of the free list, or `key` if the free list is empty
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn next_key(&self, key: K) -> K
+    where
+        K: ContiguousIx,
+    {
+        K::try_new(self.free_head).unwrap_or(key)
+    }
+}
+
+impl<S, K, const N: usize> Index<K> for CowPool<S, K, N>
+where
+    S: SlotRef,
+    K: ContiguousIx,
+{
+    type Output = S::Value;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn index(&self, index: K) -> &Self::Output {
+        self.get_slot(index).expect("key to be valid").value()
+    }
+}
+
+impl<S, K, const N: usize> IndexMut<K> for CowPool<S, K, N>
+where
+    S: SlotMut + SlotRef + Clone,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn index_mut(&mut self, index: K) -> &mut Self::Output {
+        self.get_slot_mut(index)
+            .expect("key to be valid")
+            .value_mut()
+    }
+}
+
+impl<S, K, V, const N: usize> Insert<K, V> for CowPool<S, K, N>
+where
+    S: KeySlot<K> + InitFrom<V> + Clone,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_insert(&mut self, v: V) -> Result<K, V> {
+        if let Some((key, slot)) = self.alloc() {
+            slot.set_value(v);
+            return Ok(key);
+        }
+        let mut v = Some(v);
+        self.try_push(|| S::from_value(v.take().unwrap()))
+            .ok_or_else(|| v.unwrap())
+    }
+}
+
+impl<S, K, const N: usize> InsertEmpty<K> for CowPool<S, K, N>
+where
+    S: KeySlot<K> + Clone,
+    S::Value: Container + Default,
+    K: ContiguousIx,
+{
+    #[inline]
+    fn try_insert_empty(&mut self) -> Result<K, ()> {
+        if let Some((key, slot)) = self.alloc() {
+            slot.set_default_value();
+            return Ok(key);
+        }
+        self.try_push(S::default_value).ok_or(())
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_unique_empty(&mut self) -> Result<K, ()> {
+        self.try_insert_empty()
+    }
+}
+
+impl<S, K, C, const N: usize> InsertWithCapacity<K, C> for CowPool<S, K, N>
+where
+    S: KeySlot<K> + Clone,
+    S::Value: Container + WithCapacity<C>,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_with_capacity(&mut self, capacity: C) -> K {
+        self.insert(WithCapacity::new_with_capacity(capacity))
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_insert_with_capacity(&mut self, capacity: C) -> Result<K, ()> {
+        self.try_insert(WithCapacity::new_with_capacity(capacity))
+            .map_err(|_| ())
+    }
+}
+
+impl<'a, S, K, const N: usize> InsertFromSlice<'a, K> for CowPool<S, K, N>
+where
+    S: KeySlot<K> + Clone,
+    S::Value: Container + From<&'a [Self::Elem]>,
+    Self::Elem: 'a,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_from_slice(&mut self, slice: &'a [Self::Elem]) -> K {
+        self.insert(S::Value::from(slice))
+    }
+}
+
+impl<S, K, const N: usize> Pool<K> for CowPool<S, K, N>
+where
+    S: KeySlot<K> + Clone,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn delete(&mut self, key: K) {
+        let next = self.next_key(key);
+        let ix = key.index();
+        if let Some(slot) = self.slot_mut(ix) {
+            slot.set_key(next);
+            self.free_head = ix;
+            self.free_slots += 1;
+        }
+    }
+}
+
+impl<S, K, const N: usize> ObjectPool<K> for CowPool<S, K, N>
+where
+    S: KeySlot<K> + Clone,
+    K: ContiguousIx,
+{
+    type Object = S::Value;
+}
+
+impl<S, K, const N: usize> Take<K, S::Value> for CowPool<S, K, N>
+where
+    S: KeySlot<K> + Clone,
+    K: ContiguousIx,
+{
+    #[inline]
+    fn try_take(&mut self, key: K) -> Option<S::Value> {
+        let next = self.next_key(key);
+        let ix = key.index();
+        let value = self.slot_mut(ix)?.try_swap_key(next)?;
+        self.free_head = ix;
+        self.free_slots += 1;
+        Some(value)
+    }
+}
+
+impl<S, K, const N: usize> GetRef<K, S::Value> for CowPool<S, K, N>
+where
+    S: SlotRef,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at(&self, key: K) -> Option<&S::Value> {
+        self.get_slot(key)?.try_value()
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn at(&self, key: K) -> &S::Value {
+        &self[key]
+    }
+}
+
+impl<S, K, const N: usize> GetMut<K, S::Value> for CowPool<S, K, N>
+where
+    S: SlotMut + Clone,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at_mut(&mut self, key: K) -> Option<&mut S::Value> {
+        self.get_slot_mut(key)?.try_value_mut()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use either::Either;
+    use rand::{Rng, SeedableRng};
+    use rand_xoshiro::Xoshiro256StarStar;
+
+    use super::*;
+    use crate::pool::RemovePool;
+
+    type TestPool = CowPool<Either<u32, u64>, u32, 4>;
+
+    #[test]
+    fn basic_cow_pool_usage() {
+        let mut pool = TestPool::new();
+        let keys: Vec<u32> = (0..10).map(|i| pool.insert(i)).collect();
+        assert_eq!(keys, (0..10).collect::<Vec<_>>());
+        assert_eq!(pool.total_slots(), 10);
+        assert_eq!(pool.chunks(), 3);
+
+        let mut fork = pool.clone();
+        assert_eq!(pool.shared_chunks(), 3);
+        *fork.at_mut(5) = 50;
+        assert_eq!(fork.shared_chunks(), 2);
+        assert_eq!(pool.at(5), &5);
+        assert_eq!(fork.at(5), &50);
+
+        assert_eq!(fork.remove(1), 1);
+        fork.delete(9);
+        assert_eq!(fork.free_slots(), 2);
+        assert_eq!(fork.shared_chunks(), 0);
+        assert_eq!(pool.free_slots(), 0);
+        assert_eq!(pool.at(1), &1);
+        assert_eq!(pool.at(9), &9);
+
+        assert_eq!(fork.insert(90), 9);
+        assert_eq!(fork.insert(10), 1);
+        assert_eq!(fork.insert(11), 10);
+        assert_eq!(fork.try_at(11), None);
+        assert_eq!(fork[10], 11);
+        fork[10] = 12;
+        assert_eq!(fork.try_at(10), Some(&12));
+        assert_eq!(pool.total_slots(), 10);
+        assert_eq!(fork.total_slots(), 11);
+
+        pool.clear();
+        assert_eq!(pool.total_slots(), 0);
+        assert_eq!(pool.insert(3), 0);
+    }
+
+    #[test]
+    fn cow_pool_full() {
+        let mut pool: CowPool<Either<u8, u8>, u8, 16> = CowPool::new();
+        for i in 0..=255 {
+            assert_eq!(pool.insert(i), i);
+        }
+        assert_eq!(pool.try_insert(0), Err(0));
+        pool.delete(7);
+        assert_eq!(pool.try_insert(0), Ok(7));
+    }
+
+    #[test]
+    fn cow_pool_fork_stress() {
+        let mut rng = Xoshiro256StarStar::from_seed([0x33; 32]);
+        let mut forks = vec![(TestPool::new(), std::collections::BTreeMap::new())];
+        for i in 0..4000 {
+            let f = rng.gen_range(0..forks.len());
+            let (pool, live) = &mut forks[f];
+            match rng.gen_range(0..10) {
+                0..=3 => {
+                    let key = pool.insert(i);
+                    assert_eq!(live.insert(key, i), None);
+                }
+                4..=5 if !live.is_empty() => {
+                    let key = *live.keys().nth(rng.gen_range(0..live.len())).unwrap();
+                    assert_eq!(pool.try_remove(key), live.remove(&key));
+                }
+                6..=7 if !live.is_empty() => {
+                    let key = *live.keys().nth(rng.gen_range(0..live.len())).unwrap();
+                    *pool.at_mut(key) = i;
+                    live.insert(key, i);
+                }
+                8 if forks.len() < 8 => {
+                    let fork = forks[f].clone();
+                    forks.push(fork);
+                }
+                9 if forks.len() > 1 => {
+                    forks.swap_remove(f);
+                }
+                _ => {}
+            }
+        }
+        for (pool, live) in forks {
+            assert_eq!(pool.free_slots(), pool.total_slots() - live.len());
+            for (key, value) in live {
+                assert_eq!(pool.at(key), &value);
+            }
+        }
+    }
+}
@@ -0,0 +1,279 @@
+/*!
+Mark-and-sweep garbage collection for pools whose values reference other keys
+*/
+use crate::{index::ContiguousIx, slot::SlotRef};
+
+use super::{
+    slab::{FreeList, OccupancyList, SlabPool},
+    GetRef, Pool,
+};
+
+/// A value which references keys of type `K`
+///
+/// A value may implement `Trace<K>` for several key types, allowing it to reference keys in several pools.
+pub trait Trace<K> {
+    /// Call `f` on every key of type `K` referenced by this value
+    fn trace<F: FnMut(K)>(&self, f: F);
+}
+
+impl<K: Copy> Trace<K> for [K] {
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn trace<F: FnMut(K)>(&self, f: F) {
+        self.iter().copied().for_each(f)
+    }
+}
+
+impl<K: Copy> Trace<K> for Vec<K> {
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn trace<F: FnMut(K)>(&self, f: F) {
+        self.as_slice().trace(f)
+    }
+}
+
+impl<K: Copy, const N: usize> Trace<K> for [K; N] {
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn trace<F: FnMut(K)>(&self, f: F) {
+        self.as_slice().trace(f)
+    }
+}
+
+impl<K: Copy> Trace<K> for Option<K> {
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn trace<F: FnMut(K)>(&self, f: F) {
+        self.iter().copied().for_each(f)
+    }
+}
+
+/// The set of keys of type `K` marked as reachable during a garbage collection
+///
+/// Keys are marked with [`Marks::mark`], and then traced through a pool with [`Marks::trace`], which marks every key reachable from a marked key.
+/// To collect several pools referencing each other, trace each with [`Marks::trace_with`] until [`Marks::is_done`] holds for all of them.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Marks<K> {
+    marked: Vec<bool>,
+    pending: Vec<K>,
+}
+
+impl<K> Default for Marks<K> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn default() -> Self {
+        Marks {
+            marked: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+}
+
+impl<K: ContiguousIx> Marks<K> {
+    /// Create a new, empty set of marks
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new() -> Marks<K> {
+        Self::default()
+    }
+
+    /// Whether a key has been marked as reachable
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn is_marked(&self, key: K) -> bool {
+        self.marked.get(key.index()).copied().unwrap_or(false)
+    }
+
+    /// Whether every marked key has been traced
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn is_done(&self) -> bool {
+        self.pending.is_empty()
+    }
+
+    /// Mark a key as reachable, returning `false` if it was already marked
+    ///
+    /// Newly marked keys are traced by the next call to [`Marks::trace`] or [`Marks::trace_with`]
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn mark(&mut self, key: K) -> bool {
+        let ix = key.index();
+        if ix >= self.marked.len() {
+            self.marked.resize(ix + 1, false);
+        }
+        if self.marked[ix] {
+            return false;
+        }
+        self.marked[ix] = true;
+        self.pending.push(key);
+        true
+    }
+
+    /// Mark every key reachable from a marked key through the values of `pool`
+    pub fn trace<P, V>(&mut self, pool: &P)
+    where
+        P: GetRef<K, V>,
+        V: Trace<K> + ?Sized,
+    {
+        while let Some(key) = self.pending.pop() {
+            pool.at(key).trace(|child| {
+                self.mark(child);
+            });
+        }
+    }
+
+    /// Mark every key reachable from a marked key through the values of `pool`, marking any keys of type `L` they reference in `other`
+    ///
+    /// Keys marked in `other` are not traced; the caller should repeatedly trace each pool until every set of marks is done.
+    pub fn trace_with<P, V, L>(&mut self, pool: &P, other: &mut Marks<L>)
+    where
+        P: GetRef<K, V>,
+        V: Trace<K> + Trace<L> + ?Sized,
+        L: ContiguousIx,
+    {
+        while let Some(key) = self.pending.pop() {
+            let value = pool.at(key);
+            Trace::<K>::trace(value, |child| {
+                self.mark(child);
+            });
+            Trace::<L>::trace(value, |foreign| {
+                other.mark(foreign);
+            });
+        }
+    }
+
+    /// Delete every live key in `pool` which has not been marked, returning the number of keys deleted
+    ///
+    /// All marked keys should have been traced beforehand.
+    pub fn sweep<S, F>(&self, pool: &mut SlabPool<S, K, F>) -> usize
+    where
+        F: FreeList<[S], K> + OccupancyList<[S], K>,
+        S: SlotRef,
+    {
+        debug_assert!(self.is_done(), "sweeping with untraced marks");
+        let garbage: Vec<K> = pool.keys().filter(|key| !self.is_marked(*key)).collect();
+        for key in garbage.iter() {
+            pool.delete(*key);
+        }
+        garbage.len()
+    }
+}
+
+/// Delete every key in `pool` which is not reachable from `roots`, returning the number of keys deleted
+///
+/// A key is reachable if it is a root, or referenced by the value of a reachable key as given by its [`Trace`] implementation.
+/// The pool's free list must know which slots are live, as for a [`BitmapFree`](super::slab::BitmapFree), or an
+/// [`IntrusiveFree`](super::slab::IntrusiveFree) or [`LinkedFree`](super::slab::LinkedFree) over slots which can tell keys from values, such as `Either`.
+pub fn collect<S, K, F>(pool: &mut SlabPool<S, K, F>, roots: impl IntoIterator<Item = K>) -> usize
+where
+    S: SlotRef,
+    S::Value: Trace<K>,
+    K: ContiguousIx,
+    F: FreeList<[S], K> + OccupancyList<[S], K>,
+{
+    let mut marks = Marks::new();
+    for root in roots {
+        marks.mark(root);
+    }
+    marks.trace(pool);
+    marks.sweep(pool)
+}
+
+#[cfg(test)]
+mod test {
+    use either::Either;
+
+    use super::*;
+    use crate::{
+        pool::{
+            slab::{BitmapSlabPool, FreeListCapacity, IntrusiveFree, LinkedFree},
+            GetMut, Insert,
+        },
+        slot::{DefaultSlot, SlotMut},
+    };
+
+    #[test]
+    fn collect_slab_pool() {
+        fn check<S, F>()
+        where
+            S: SlotRef<Value = Vec<u32>> + SlotMut,
+            F: OccupancyList<[S], u32> + FreeListCapacity<[S], u32> + Default,
+        {
+            let mut pool: SlabPool<S, u32, F> = SlabPool::new();
+            let leaf: u32 = pool.insert(vec![]);
+            let mid: u32 = pool.insert(vec![leaf, leaf]);
+            let root: u32 = pool.insert(vec![mid]);
+            let orphan: u32 = pool.insert(vec![leaf]);
+            let cycle: u32 = pool.insert(vec![]);
+            *pool.at_mut(cycle) = vec![cycle, root];
+            let dead_cycle: u32 = pool.insert(vec![]);
+            *pool.at_mut(dead_cycle) = vec![dead_cycle, orphan];
+
+            assert_eq!(collect(&mut pool, [cycle]), 2);
+            assert_eq!(pool.keys().collect::<Vec<_>>(), [leaf, mid, root, cycle]);
+            assert_eq!(collect(&mut pool, [mid]), 2);
+            assert_eq!(pool.keys().collect::<Vec<_>>(), [leaf, mid]);
+            assert_eq!(collect(&mut pool, []), 2);
+            assert_eq!(pool.keys().count(), 0);
+            assert_eq!(pool.free_slots(), 6);
+        }
+        check::<Either<u32, Vec<u32>>, IntrusiveFree>();
+        check::<Either<(u32, u32), Vec<u32>>, LinkedFree>();
+    }
+
+    #[test]
+    fn collect_bitmap_slab_pool() {
+        let mut pool: BitmapSlabPool<DefaultSlot<Option<u8>>, u8> = BitmapSlabPool::new();
+        let a: u8 = pool.insert(None);
+        let b: u8 = pool.insert(Some(a));
+        let c: u8 = pool.insert(Some(b));
+        assert_eq!(collect(&mut pool, [b]), 1);
+        assert!(pool.contains_key(a) && pool.contains_key(b) && !pool.contains_key(c));
+        // Collection is idempotent
+        assert_eq!(collect(&mut pool, [b]), 0);
+    }
+
+    /// A value referencing both "terms" with `u32` keys and "types" with `u16` keys
+    #[derive(Debug, Clone, Default)]
+    struct Node {
+        terms: Vec<u32>,
+        types: Vec<u16>,
+    }
+
+    impl Trace<u32> for Node {
+        fn trace<F: FnMut(u32)>(&self, f: F) {
+            self.terms.trace(f)
+        }
+    }
+
+    impl Trace<u16> for Node {
+        fn trace<F: FnMut(u16)>(&self, f: F) {
+            self.types.trace(f)
+        }
+    }
+
+    #[test]
+    fn collect_across_pools() {
+        let mut terms: BitmapSlabPool<DefaultSlot<Node>, u32> = BitmapSlabPool::new();
+        let mut types: BitmapSlabPool<DefaultSlot<Node>, u16> = BitmapSlabPool::new();
+        let int: u16 = types.insert(Node::default());
+        let _unused: u16 = types.insert(Node::default());
+        let x: u32 = terms.insert(Node::default());
+        // Types may reference terms, e.g. for dependent types
+        let vec: u16 = types.insert(Node {
+            terms: vec![x],
+            types: vec![int],
+        });
+        let y: u32 = terms.insert(Node {
+            terms: vec![],
+            types: vec![vec],
+        });
+        let _garbage: u32 = terms.insert(Node {
+            terms: vec![x, y],
+            types: vec![int],
+        });
+
+        let mut term_marks = Marks::new();
+        let mut type_marks = Marks::new();
+        term_marks.mark(y);
+        while !term_marks.is_done() || !type_marks.is_done() {
+            term_marks.trace_with(&terms, &mut type_marks);
+            type_marks.trace_with(&types, &mut term_marks);
+        }
+        assert_eq!(term_marks.sweep(&mut terms), 1);
+        assert_eq!(type_marks.sweep(&mut types), 1);
+        assert_eq!(terms.keys().collect::<Vec<_>>(), [x, y]);
+        assert_eq!(types.keys().collect::<Vec<_>>(), [int, vec]);
+    }
+}
@@ -0,0 +1,246 @@
+/*!
+An interning pool, which deduplicates values so that equal values always receive the same key
+*/
+use std::{
+    borrow::Borrow,
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hash},
+    ops::Index,
+};
+
+use hashbrown::{hash_map::RawEntryMut, HashMap};
+
+use crate::index::ContiguousIx;
+
+use super::*;
+
+/// A pool which stores each distinct value of type `V` exactly once, returning the same key of type `K` for equal values
+///
+/// Values are stored contiguously in an [`Arena`], indexed by a table of keys hashed by the value they point to using `S`, so that each value is stored
+/// only once. Values may not be removed, since other holders of the same key would observe the removal.
+#[derive(Debug, Clone)]
+pub struct InternPool<V, K = usize, S = RandomState> {
+    values: Arena<Vec<V>, K>,
+    table: HashMap<K, (), ()>,
+    hasher: S,
+}
+
+impl<V, K, S: Default> Default for InternPool<V, K, S> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn default() -> Self {
+        Self::with_hasher(S::default())
+    }
+}
+
+impl<V, K> InternPool<V, K> {
+    /// Create a new, empty interning pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new() -> InternPool<V, K> {
+        Self::default()
+    }
+}
+
+impl<V, K, S> InternPool<V, K, S> {
+    /// Create a new, empty interning pool which hashes values using `hasher`
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn with_hasher(hasher: S) -> InternPool<V, K, S> {
+        InternPool {
+            values: Arena::from(Vec::new()),
+            table: HashMap::with_hasher(()),
+            hasher,
+        }
+    }
+
+    /// Get the number of distinct values in this pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn len(&self) -> usize {
+        self.values.0.len()
+    }
+
+    /// Check whether this pool is empty
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn is_empty(&self) -> bool {
+        self.values.0.is_empty()
+    }
+
+    /// Get the values in this pool as a slice, in order of interning
+    ///
+    /// The value for key `k` is at index `k.index()`.
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn values(&self) -> &[V] {
+        &self.values.0
+    }
+
+    /// Get the underlying arena of values
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn arena(&self) -> &Arena<Vec<V>, K> {
+        &self.values
+    }
+}
+
+impl<V, K, S> InternPool<V, K, S>
+where
+    V: Hash + Eq,
+    K: ContiguousIx,
+    S: BuildHasher,
+{
+    /// Get the key of a value equal to `value`, or `None` if no such value has been interned
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn get_key<Q>(&self, value: &Q) -> Option<K>
+    where
+        V: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let values = &self.values.0;
+        self.table
+            .raw_entry()
+            .from_hash(self.hasher.hash_one(value), |key| {
+                values[key.index()].borrow() == value
+            })
+            .map(|(key, _)| *key)
+    }
+
+    /// Intern a value, returning the key of an equal value if one has already been interned
+    ///
+    /// Panics if the pool is out of keys
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn intern(&mut self, value: V) -> K {
+        match self.try_intern(value) {
+            Ok(key) => key,
+            Err(_) => panic!("intern pool is out of keys"),
+        }
+    }
+
+    /// Intern a value, returning the key of an equal value if one has already been interned
+    ///
+    /// Returns the value back if it has not already been interned and the pool is out of keys
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn try_intern(&mut self, value: V) -> Result<K, V> {
+        self.try_intern_by(value, |value| value)
+    }
+
+    /// Intern a borrowed value, only converting it to an owned `V` if an equal value has not already been interned
+    ///
+    /// Panics if the pool is out of keys
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn intern_ref<Q>(&mut self, value: &Q) -> K
+    where
+        V: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = V> + ?Sized,
+    {
+        match self.try_intern_ref(value) {
+            Ok(key) => key,
+            Err(_) => panic!("intern pool is out of keys"),
+        }
+    }
+
+    /// Intern a borrowed value, only converting it to an owned `V` if an equal value has not already been interned
+    ///
+    /// Returns the owned value if it has not already been interned and the pool is out of keys
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn try_intern_ref<Q>(&mut self, value: &Q) -> Result<K, V>
+    where
+        V: Borrow<Q>,
+        Q: Hash + Eq + ToOwned<Owned = V> + ?Sized,
+    {
+        self.try_intern_by(value, Q::to_owned)
+    }
+
+    /// Intern `value`, which borrows as a `Q`, calling `to_owned` to convert it to a `V` only if an equal value has not already been interned
+    fn try_intern_by<Q, T, F>(&mut self, value: T, to_owned: F) -> Result<K, V>
+    where
+        V: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+        T: Borrow<Q>,
+        F: FnOnce(T) -> V,
+    {
+        let hash = self.hasher.hash_one(value.borrow());
+        let InternPool {
+            values,
+            table,
+            hasher,
+        } = self;
+        let values = &mut values.0;
+        match table
+            .raw_entry_mut()
+            .from_hash(hash, |key| values[key.index()].borrow() == value.borrow())
+        {
+            RawEntryMut::Occupied(entry) => Ok(*entry.key()),
+            RawEntryMut::Vacant(entry) => {
+                let owned = to_owned(value);
+                let Some(key) = K::try_new(values.len()) else {
+                    return Err(owned);
+                };
+                values.push(owned);
+                entry
+                    .insert_with_hasher(hash, key, (), |key| hasher.hash_one(&values[key.index()]));
+                Ok(key)
+            }
+        }
+    }
+}
+
+impl<V, K, S> Index<K> for InternPool<V, K, S>
+where
+    K: ContiguousIx,
+{
+    type Output = V;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn index(&self, index: K) -> &V {
+        &self.values.0[index.index()]
+    }
+}
+
+impl<V, K, S> GetRef<K, V> for InternPool<V, K, S>
+where
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at(&self, key: K) -> Option<&V> {
+        self.values.try_at(key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn basic_intern_pool_usage() {
+        let mut pool: InternPool<String, u32> = InternPool::new();
+        assert!(pool.is_empty());
+        let a = pool.intern("a".to_string());
+        let b = pool.intern_ref("b");
+        assert_ne!(a, b);
+        assert_eq!(pool.intern_ref("a"), a);
+        assert_eq!(pool.intern("b".to_string()), b);
+        assert_eq!(pool.len(), 2);
+        assert_eq!(pool.get_key("a"), Some(a));
+        assert_eq!(pool.get_key("c"), None);
+        assert_eq!(pool.at(a), "a");
+        assert_eq!(pool[b], "b");
+        assert_eq!(pool.try_at(2), None);
+        assert_eq!(pool.values(), ["a", "b"]);
+
+        // Interning many values forces the table to be rehashed
+        let keys: Vec<u32> = (0..1000).map(|i| pool.intern(i.to_string())).collect();
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(pool.intern_ref(&*i.to_string()), *key);
+            assert_eq!(pool[*key], i.to_string());
+        }
+        assert_eq!(pool.len(), 1002);
+    }
+
+    #[test]
+    fn intern_pool_full() {
+        let mut pool: InternPool<u16, u8> = InternPool::default();
+        for i in 0..256 {
+            assert_eq!(pool.intern(i), i as u8);
+        }
+        assert_eq!(pool.try_intern(7), Ok(7));
+        assert_eq!(pool.try_intern(256), Err(256));
+        assert_eq!(pool.try_intern_ref(&3), Ok(3));
+        assert_eq!(pool.arena().try_at(255), Some(&255));
+    }
+}
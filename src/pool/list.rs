@@ -0,0 +1,575 @@
+/*!
+A pool packing many small lists into a single buffer, in the style of `cranelift-entity`'s `ListPool`
+*/
+use std::ops::Range;
+
+use crate::index::ContiguousIx;
+
+use super::{
+    container::{
+        array::{InsertFromSlice, SliceMutPool, SliceRefPool},
+        iter::{IterMutPool, IterPool},
+        map::{GetIndex, GetIndexMut},
+        stack::StackPool,
+        ContainerPool, InsertEmpty, IsEmptyPool, LenPool,
+    },
+    *,
+};
+
+/// A pool of lists of `T`, all stored in a single buffer
+///
+/// Each non-empty list is stored in a block of `4 << c` elements for some size class `c`, where the first element of the block is a header holding
+/// the length of the list, and the rest hold the list's elements. A list's size class is always the smallest able to hold its elements, so the header
+/// is the only per-list overhead; this does mean that elements must be able to represent their list's length, as for a [`ContiguousIx`].
+///
+/// The key of a list is the offset of its first element in the buffer, with the key `0` reserved for the empty list. Pushing to or popping from a
+/// list may move it to a block of a different size class, changing its key; see [`StackPool::into_pushed`] and [`StackPool::into_popped`].
+/// Free blocks are kept in per-class free lists, and reused before the buffer is grown.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ListPool<T> {
+    data: Vec<T>,
+    free: Vec<Vec<u32>>,
+}
+
+impl<T> Default for ListPool<T> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Get the size class of the block holding a list of length `len`
+#[cfg_attr(not(tarpaulin), inline(always))]
+fn size_class(len: usize) -> usize {
+    (usize::BITS - 2 - (len | 3).leading_zeros()) as usize
+}
+
+/// Get the number of elements, including the header, in a block of size class `class`
+#[cfg_attr(not(tarpaulin), inline(always))]
+fn class_size(class: usize) -> usize {
+    4 << class
+}
+
+impl<T> ListPool<T> {
+    /// Create a new, empty pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new() -> ListPool<T> {
+        ListPool {
+            data: Vec::new(),
+            free: Vec::new(),
+        }
+    }
+
+    /// Create a new, empty pool with space for `capacity` elements, including headers
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn with_capacity(capacity: usize) -> ListPool<T> {
+        ListPool {
+            data: Vec::with_capacity(capacity),
+            free: Vec::new(),
+        }
+    }
+
+    /// Get the total size of the buffer, including headers and free blocks
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn buffer_len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Get the number of free blocks in this pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn free_blocks(&self) -> usize {
+        self.free.iter().map(Vec::len).sum()
+    }
+
+    /// Delete every list in this pool, invalidating all keys
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.free.clear();
+    }
+}
+
+impl<T> ListPool<T>
+where
+    T: ContiguousIx,
+{
+    /// Get the length of the list with a given key
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn list_len(&self, key: u32) -> usize {
+        if key == 0 {
+            0
+        } else {
+            self.data[key as usize - 1].index()
+        }
+    }
+
+    /// Get the range of the buffer holding the elements of the list with a given key, or `None` if the key is invalid
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn range(&self, key: u32) -> Option<Range<usize>> {
+        if key == 0 {
+            return Some(0..0);
+        }
+        let start = key as usize;
+        let end = start + self.data.get(start - 1)?.index();
+        if end > self.data.len() {
+            return None;
+        }
+        Some(start..end)
+    }
+
+    /// Allocate a block of a given size class, returning the key of its first element
+    ///
+    /// The block's header and elements are left unspecified.
+    fn alloc(&mut self, class: usize) -> Option<u32> {
+        if let Some(key) = self.free.get_mut(class).and_then(Vec::pop) {
+            return Some(key);
+        }
+        let block = self.data.len();
+        let end = block + class_size(class);
+        if end > u32::MAX as usize {
+            return None;
+        }
+        self.data.resize(end, T::new(0));
+        Some(block as u32 + 1)
+    }
+
+    /// Free the block of a given size class holding the list with key `key`
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn free_block(&mut self, key: u32, class: usize) {
+        if class >= self.free.len() {
+            self.free.resize_with(class + 1, Vec::new);
+        }
+        self.free[class].push(key)
+    }
+
+    /// Move the first `len` elements of the list with key `key` to a new block of size class `class`, freeing the old block
+    ///
+    /// Returns the new key, or `None`, leaving the pool unchanged, if no such block can be allocated. The new block's header is left unspecified.
+    fn realloc(&mut self, key: u32, len: usize, class: usize) -> Option<u32> {
+        let new_key = self.alloc(class)?;
+        if key != 0 {
+            let start = key as usize;
+            self.data.copy_within(start..start + len, new_key as usize);
+            self.free_block(key, size_class(self.list_len(key)));
+        }
+        Some(new_key)
+    }
+
+    /// Set the length of the list with a given non-zero key
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn set_len(&mut self, key: u32, len: T) {
+        self.data[key as usize - 1] = len;
+    }
+}
+
+impl<T> Pool<u32> for ListPool<T>
+where
+    T: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn delete(&mut self, key: u32) {
+        if key != 0 {
+            self.free_block(key, size_class(self.list_len(key)))
+        }
+    }
+}
+
+impl<T> ContainerPool<u32> for ListPool<T>
+where
+    T: ContiguousIx,
+{
+    type Elem = T;
+}
+
+impl<T> IsEmptyPool<u32> for ListPool<T>
+where
+    T: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn key_is_empty(&self, key: u32) -> bool {
+        self.list_len(key) == 0
+    }
+}
+
+impl<T> LenPool<u32> for ListPool<T>
+where
+    T: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn key_len(&self, key: u32) -> usize {
+        self.list_len(key)
+    }
+}
+
+impl<T> GetIndex<u32, usize, T> for ListPool<T>
+where
+    T: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn get_index(&self, key: u32, elem: usize) -> Option<&T> {
+        self.try_at(key)?.get(elem)
+    }
+}
+
+impl<T> GetIndexMut<u32, usize, T> for ListPool<T>
+where
+    T: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn get_index_mut(&mut self, key: u32, elem: usize) -> Option<&mut T> {
+        self.try_at_mut(key)?.get_mut(elem)
+    }
+}
+
+impl<T> SliceRefPool<u32> for ListPool<T>
+where
+    T: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn slice_at(&self, key: u32) -> &[T] {
+        self.at(key)
+    }
+}
+
+impl<T> SliceMutPool<u32> for ListPool<T>
+where
+    T: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn slice_at_mut(&mut self, key: u32) -> &mut [T] {
+        self.at_mut(key)
+    }
+}
+
+impl<T> IterPool<u32> for ListPool<T>
+where
+    T: ContiguousIx,
+{
+    type Iter<'a>
+        = std::slice::Iter<'a, T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn iter_at(&self, key: u32) -> Self::Iter<'_> {
+        self.at(key).iter()
+    }
+}
+
+impl<T> IterMutPool<u32> for ListPool<T>
+where
+    T: ContiguousIx,
+{
+    type IterMut<'a>
+        = std::slice::IterMut<'a, T>
+    where
+        Self: 'a,
+        T: 'a;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn iter_at_mut(&mut self, key: u32) -> Self::IterMut<'_> {
+        self.at_mut(key).iter_mut()
+    }
+}
+
+impl<T> GetRef<u32, [T]> for ListPool<T>
+where
+    T: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at(&self, key: u32) -> Option<&[T]> {
+        let range = self.range(key)?;
+        Some(&self.data[range])
+    }
+}
+
+impl<T> GetMut<u32, [T]> for ListPool<T>
+where
+    T: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at_mut(&mut self, key: u32) -> Option<&mut [T]> {
+        let range = self.range(key)?;
+        Some(&mut self.data[range])
+    }
+}
+
+impl<'a, T> Insert<u32, &'a [T]> for ListPool<T>
+where
+    T: ContiguousIx,
+{
+    fn try_insert(&mut self, val: &'a [T]) -> Result<u32, &'a [T]> {
+        if val.is_empty() {
+            return Ok(0);
+        }
+        let Some(len) = T::try_new(val.len()) else {
+            return Err(val);
+        };
+        let Some(key) = self.alloc(size_class(val.len())) else {
+            return Err(val);
+        };
+        let start = key as usize;
+        self.set_len(key, len);
+        self.data[start..start + val.len()].copy_from_slice(val);
+        Ok(key)
+    }
+}
+
+impl<T> InsertEmpty<u32> for ListPool<T> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_insert_empty(&mut self) -> Result<u32, ()> {
+        Ok(0)
+    }
+}
+
+impl<'a, T> InsertFromSlice<'a, u32> for ListPool<T>
+where
+    T: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_from_slice(&mut self, slice: &'a [T]) -> u32 {
+        self.insert(slice)
+    }
+}
+
+impl<T> StackPool<u32> for ListPool<T>
+where
+    T: ContiguousIx,
+{
+    fn try_into_popped(&mut self, key: u32) -> Result<Option<(u32, T)>, ()> {
+        let len = self.list_len(key);
+        if len == 0 {
+            return Ok(None);
+        }
+        let item = self.data[key as usize + len - 1];
+        if len == 1 {
+            self.delete(key);
+            return Ok(Some((0, item)));
+        }
+        let class = size_class(len - 1);
+        let new_key = if class != size_class(len) {
+            self.realloc(key, len - 1, class).ok_or(())?
+        } else {
+            key
+        };
+        self.set_len(new_key, T::new(len - 1));
+        Ok(Some((new_key, item)))
+    }
+
+    fn try_into_pushed(&mut self, key: u32, item: T) -> Result<u32, T> {
+        let len = self.list_len(key);
+        let Some(new_len) = T::try_new(len + 1) else {
+            return Err(item);
+        };
+        let class = size_class(len + 1);
+        let new_key = if key == 0 || class != size_class(len) {
+            match self.realloc(key, len, class) {
+                Some(new_key) => new_key,
+                None => return Err(item),
+            }
+        } else {
+            key
+        };
+        self.set_len(new_key, new_len);
+        self.data[new_key as usize + len] = item;
+        Ok(new_key)
+    }
+
+    fn try_pop(&mut self, key: u32) -> Result<Option<T>, ()> {
+        let len = self.list_len(key);
+        if len == 0 {
+            return Ok(None);
+        }
+        if size_class(len - 1) != size_class(len) {
+            return Err(());
+        }
+        self.set_len(key, T::new(len - 1));
+        Ok(Some(self.data[key as usize + len - 1]))
+    }
+
+    fn try_push(&mut self, key: u32, item: T) -> Result<(), T> {
+        let len = self.list_len(key);
+        if key == 0 || size_class(len + 1) != size_class(len) {
+            return Err(item);
+        }
+        let Some(new_len) = T::try_new(len + 1) else {
+            return Err(item);
+        };
+        self.set_len(key, new_len);
+        self.data[key as usize + len] = item;
+        Ok(())
+    }
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn key_capacity(&self, key: u32) -> usize {
+        if key == 0 {
+            0
+        } else {
+            class_size(size_class(self.list_len(key))) - 1
+        }
+    }
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn cleared_key(&mut self, key: u32) -> u32 {
+        self.delete(key);
+        0
+    }
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn clear_key(&mut self, key: u32) -> Result<(), ()> {
+        if key != 0 {
+            if size_class(self.list_len(key)) != 0 {
+                return Err(());
+            }
+            self.set_len(key, T::new(0));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use rand::{Rng, SeedableRng};
+    use rand_xoshiro::Xoshiro256StarStar;
+
+    use super::*;
+    use crate::{
+        entity::list::EntityList,
+        pool::container::{array::VecPool, LenPool},
+    };
+
+    fn is_vec_pool<P: VecPool<u32>>(_pool: &P) {}
+
+    #[test]
+    fn size_classes() {
+        assert_eq!(size_class(0), 0);
+        assert_eq!(size_class(3), 0);
+        assert_eq!(size_class(4), 1);
+        assert_eq!(size_class(7), 1);
+        assert_eq!(size_class(8), 2);
+        assert_eq!(size_class(255), 6);
+        assert_eq!(size_class(256), 7);
+        for len in 0..1000 {
+            assert!(len < class_size(size_class(len)));
+            assert!(size_class(len) == 0 || class_size(size_class(len) - 1) <= len);
+        }
+    }
+
+    #[test]
+    fn basic_list_pool_usage() {
+        let mut pool: ListPool<u32> = ListPool::new();
+        is_vec_pool(&pool);
+        let mut v = EntityList::new(&mut pool);
+        assert_eq!(v.len(&pool), 0);
+        assert_eq!(v.pop(&mut pool), None);
+        assert_eq!(pool.buffer_len(), 0);
+        for i in 0..5 {
+            v.push(i, &mut pool);
+        }
+        assert_eq!(v.as_slice(&pool), [0, 1, 2, 3, 4]);
+        assert_eq!(v.len(&pool), 5);
+        // Blocks of size 4 and 8 have been allocated, and the first freed on the move
+        assert_eq!(pool.buffer_len(), 12);
+        assert_eq!(pool.free_blocks(), 1);
+        let u = EntityList::from_slice(&[7, 8], &mut pool);
+        assert_eq!(pool.buffer_len(), 12);
+        assert_eq!(pool.free_blocks(), 0);
+        assert_eq!(u.as_slice(&pool), [7, 8]);
+        *v.get_mut(1, &mut pool).unwrap() = 9;
+        v.as_slice_mut(&mut pool)[2] = 10;
+        assert_eq!(v.get(1, &pool), Some(&9));
+        assert_eq!(v.pop(&mut pool), Some(4));
+        assert_eq!(v.pop(&mut pool), Some(3));
+        assert_eq!(v.as_slice(&pool), [0, 9, 10]);
+        assert_eq!(pool.free_blocks(), 1);
+        while v.pop(&mut pool).is_some() {}
+        assert!(v.is_empty(&pool));
+        assert_eq!(v, EntityList::new(&mut pool));
+        assert_eq!(u.as_slice(&pool), [7, 8]);
+    }
+
+    #[test]
+    fn pinned_list_pool_ops() {
+        let mut pool: ListPool<u16> = ListPool::new();
+        assert_eq!(pool.try_push(0, 1), Err(1));
+        let key: u32 = pool.insert(&[1, 2][..]);
+        assert_eq!(pool.key_capacity(key), 3);
+        assert_eq!(pool.key_capacity(0), 0);
+        assert_eq!(pool.try_push(key, 3), Ok(()));
+        assert_eq!(pool.try_push(key, 4), Err(4));
+        assert_eq!(pool.at(key), [1, 2, 3]);
+        let key = pool.into_pushed(key, 4);
+        assert_eq!(pool.key_capacity(key), 7);
+        assert_eq!(pool.try_pop(key), Err(()));
+        assert_eq!(pool.clear_key(key), Err(()));
+        let (key, four) = pool.into_popped(key).unwrap();
+        assert_eq!(four, 4);
+        assert_eq!(pool.try_pop(key), Ok(Some(3)));
+        assert_eq!(pool.pop(key), Some(2));
+        assert_eq!(pool.pop(key), Some(1));
+        // A pinned pop may leave an empty list in a block
+        assert_ne!(key, 0);
+        assert_eq!(pool.pop(key), None);
+        assert_eq!(pool.try_into_popped(key), Ok(None));
+        pool.push(key, 5);
+        assert_eq!(pool.clear_key(key), Ok(()));
+        assert_eq!(pool.key_len(key), 0);
+        assert_eq!(pool.cleared_key(key), 0);
+        assert_eq!(pool.free_blocks(), 2);
+        assert_eq!(pool.try_at(1000), None);
+    }
+
+    #[test]
+    fn list_pool_length_overflow() {
+        let mut pool: ListPool<u8> = ListPool::new();
+        let full = [0; 255];
+        let key: u32 = pool.insert(&full[..]);
+        assert_eq!(pool.key_len(key), 255);
+        assert_eq!(pool.try_into_pushed(key, 1), Err(1));
+        assert_eq!(pool.try_insert(&[0; 256][..]), Err(&[0; 256][..]));
+        assert_eq!(pool.at(key), full);
+    }
+
+    #[test]
+    fn list_pool_stress() {
+        let mut rng = Xoshiro256StarStar::from_seed([7; 32]);
+        let mut pool: ListPool<u32> = ListPool::new();
+        let mut lists: Vec<(u32, Vec<u32>)> = Vec::new();
+        for i in 0..10000 {
+            let op = rng.gen_range(0..8);
+            if lists.is_empty() || op == 0 {
+                let len = rng.gen_range(0..20);
+                let model: Vec<u32> = (0..len).collect();
+                lists.push((pool.insert(&model[..]), model));
+                continue;
+            }
+            let ix = rng.gen_range(0..lists.len());
+            let (key, model) = &mut lists[ix];
+            match op {
+                1 => {
+                    pool.delete(*key);
+                    lists.swap_remove(ix);
+                }
+                2..=4 => {
+                    *key = pool.into_pushed(*key, i);
+                    model.push(i);
+                }
+                5 | 6 => {
+                    let popped = pool.into_popped(*key);
+                    assert_eq!(popped.map(|(_, v)| v), model.pop());
+                    if let Some((new_key, _)) = popped {
+                        *key = new_key;
+                    }
+                }
+                _ => {
+                    *key = pool.cleared_key(*key);
+                    model.clear();
+                }
+            }
+            for (key, model) in &lists {
+                assert_eq!(pool.at(*key), &model[..]);
+            }
+        }
+    }
+}
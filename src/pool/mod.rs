@@ -6,10 +6,26 @@ use std::marker::PhantomData;
 
 use bytemuck::{TransparentWrapper, Zeroable};
 
-use crate::index::ContiguousIx;
+use crate::index::{ContiguousIx, TrustedIx};
 
+pub mod brand;
+#[cfg(feature = "hashbrown")]
+pub mod cons;
 pub mod container;
+#[cfg(feature = "ecow")]
+pub mod cow;
+pub mod gc;
+#[cfg(feature = "hashbrown")]
+pub mod intern;
+pub mod list;
+pub mod rc;
+pub mod ring;
 pub mod slab;
+pub mod slice;
+pub mod stable;
+pub mod stats;
+pub mod tagged;
+pub mod validate;
 
 /// A pool which supports inserting values of type `V` for keys of type `K`
 pub trait Insert<K, V> {
@@ -414,6 +430,30 @@ where
     }
 }
 
+// SAFETY: a key returned by an insertion was created by `K::try_new` on the length of the arena, and as `K: TrustedIx` its index is
+// still that length. An arena only shrinks when its underlying vector is truncated, e.g. via `TransparentWrapper::peel_mut`, which is
+// not possible through the traits allowed by `GetUnchecked`
+unsafe impl<K, V> brand::GetUnchecked<K, V> for Arena<Vec<V>, K>
+where
+    K: TrustedIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    unsafe fn at_unchecked(&self, key: K) -> &V {
+        self.0.get_unchecked(key.index())
+    }
+}
+
+// SAFETY: as for `GetUnchecked`
+unsafe impl<K, V> brand::GetUncheckedMut<K, V> for Arena<Vec<V>, K>
+where
+    K: TrustedIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    unsafe fn at_unchecked_mut(&mut self, key: K) -> &mut V {
+        self.0.get_unchecked_mut(key.index())
+    }
+}
+
 /// Forward implementations of [`Pool`], [`ObjectPool`], [`Insert`], [`Take`], [`GetRef`], and [`GetMut`] to a field of type `$P`
 #[macro_export]
 macro_rules! forward_pool_traits {
@@ -540,4 +580,15 @@ mod test {
         assert_eq!(arena.try_remove(0), Some(6));
         assert_eq!(arena.try_remove(0), Some(0));
     }
+
+    fn remove_generic<K, P: RemovePool<K>>(pool: &mut P, key: K) -> P::Object {
+        pool.remove(key)
+    }
+
+    #[test]
+    fn generic_remove_pool() {
+        let mut arena = Arena::new(vec![]);
+        let key: usize = arena.insert(5);
+        assert_eq!(remove_generic(&mut arena, key), 5);
+    }
 }
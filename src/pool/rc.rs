@@ -0,0 +1,241 @@
+/*!
+A reference-counting wrapper around a pool, freeing keys when their last owner releases them
+*/
+use crate::index::ContiguousIx;
+
+use super::*;
+
+/// A wrapper around a pool `P` which keeps a reference count for every live key
+///
+/// Keys are inserted with a count of one. [`RcPool::retain`] increments a key's count, while [`RcPool::release`] decrements it, removing the key from
+/// the underlying pool once its count reaches zero. Deleting a key through [`Pool::delete`] also decrements its count, deleting it from the underlying
+/// pool at zero, and so works with pools which do not support [`Take`].
+///
+/// Counts are checked on every operation: retaining a key past `u32::MAX` owners, or retaining or releasing a key which is not live, panics.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct RcPool<P> {
+    pool: P,
+    counts: Vec<u32>,
+}
+
+impl<P> RcPool<P> {
+    /// Wrap a pool, which should not yet have handed out any keys
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new(pool: P) -> RcPool<P> {
+        RcPool {
+            pool,
+            counts: Vec::new(),
+        }
+    }
+
+    /// Get a reference to the underlying pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn inner(&self) -> &P {
+        &self.pool
+    }
+
+    /// Unwrap this pool, returning the underlying pool and discarding all reference counts
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn into_inner(self) -> P {
+        self.pool
+    }
+
+    /// Get the number of owners of a key, or `0` if it is not live
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn strong_count<K: ContiguousIx>(&self, key: K) -> usize {
+        self.counts.get(key.index()).copied().unwrap_or(0) as usize
+    }
+
+    /// Add an owner to a live key, returning the key for convenience
+    ///
+    /// Panics if the key is not live, or if its count would overflow
+    #[cfg_attr(not(tarpaulin), inline)]
+    #[track_caller]
+    pub fn retain<K: ContiguousIx>(&mut self, key: K) -> K {
+        let count = self.live_count(key, "retain");
+        *count = count.checked_add(1).expect("reference count overflow");
+        key
+    }
+
+    /// Remove an owner from a live key, taking its value from the underlying pool if this was the last owner
+    ///
+    /// Returns `None` if the key still has other owners. Panics if the key is not live.
+    #[cfg_attr(not(tarpaulin), inline)]
+    #[track_caller]
+    pub fn release<K, V>(&mut self, key: K) -> Option<V>
+    where
+        P: Take<K, V>,
+        K: ContiguousIx,
+    {
+        if self.decrement(key, "release") {
+            Some(self.pool.take(key))
+        } else {
+            None
+        }
+    }
+
+    /// Get the count of a live key, panicking if it is not live
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    fn live_count<K: ContiguousIx>(&mut self, key: K, op: &str) -> &mut u32 {
+        match self.counts.get_mut(key.index()) {
+            Some(count) if *count != 0 => count,
+            _ => panic!("{op} of key {} which is not live", key.index()),
+        }
+    }
+
+    /// Decrement the count of a live key, returning whether it reached zero
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    fn decrement<K: ContiguousIx>(&mut self, key: K, op: &str) -> bool {
+        let count = self.live_count(key, op);
+        *count -= 1;
+        *count == 0
+    }
+}
+
+impl<P, K, V> Insert<K, V> for RcPool<P>
+where
+    P: Insert<K, V>,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_insert(&mut self, val: V) -> Result<K, V> {
+        let key = self.pool.try_insert(val)?;
+        let ix = key.index();
+        if ix >= self.counts.len() {
+            self.counts.resize(ix + 1, 0);
+        }
+        self.counts[ix] = 1;
+        Ok(key)
+    }
+}
+
+impl<P, K> Pool<K> for RcPool<P>
+where
+    P: Pool<K>,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    #[track_caller]
+    fn delete(&mut self, key: K) {
+        if self.decrement(key, "delete") {
+            self.pool.delete(key)
+        }
+    }
+}
+
+impl<P, K> ObjectPool<K> for RcPool<P>
+where
+    P: ObjectPool<K>,
+    K: ContiguousIx,
+{
+    type Object = P::Object;
+}
+
+impl<P, K> SafeFreePool<K> for RcPool<P>
+where
+    P: Pool<K>,
+    K: ContiguousIx,
+{
+}
+
+impl<P, K, V: ?Sized> GetRef<K, V> for RcPool<P>
+where
+    P: GetRef<K, V>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at(&self, key: K) -> Option<&V> {
+        self.pool.try_at(key)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn at(&self, key: K) -> &V {
+        self.pool.at(key)
+    }
+}
+
+impl<P, K, V: ?Sized> GetMut<K, V> for RcPool<P>
+where
+    P: GetMut<K, V>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at_mut(&mut self, key: K) -> Option<&mut V> {
+        self.pool.try_at_mut(key)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn at_mut(&mut self, key: K) -> &mut V {
+        self.pool.at_mut(key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pool::slab::{IntrusiveFree, SlabPool};
+    use crate::slot::DefaultSlot;
+    use either::Either;
+
+    #[test]
+    fn basic_rc_pool_usage() {
+        let mut pool: RcPool<SlabPool<DefaultSlot<u32>, u32>> = RcPool::new(SlabPool::new());
+        let a: u32 = pool.insert(5);
+        let b: u32 = pool.insert(6);
+        assert_eq!(pool.strong_count(a), 1);
+        assert_eq!(pool.retain(a), a);
+        pool.retain(a);
+        assert_eq!(pool.strong_count(a), 3);
+        assert_eq!(pool.release(a), None);
+        pool.delete(a);
+        assert_eq!(pool.strong_count(a), 1);
+        *pool.at_mut(a) = 7;
+        assert_eq!(pool.release(a), Some(7));
+        assert_eq!(pool.strong_count(a), 0);
+        assert_eq!(pool.inner().free_slots(), 1);
+
+        // The free'd slot is reused with a fresh count
+        assert_eq!(pool.insert(8), a);
+        assert_eq!(pool.strong_count(a), 1);
+        assert_eq!(pool.at(a), &8);
+        assert_eq!(pool.try_at(b), Some(&6));
+        pool.delete(b);
+        assert_eq!(pool.strong_count(b), 0);
+        assert_eq!(pool.strong_count(10u32), 0);
+    }
+
+    #[test]
+    fn rc_pool_intrusive() {
+        let mut pool: RcPool<SlabPool<Either<u8, String>, u8, IntrusiveFree>> =
+            RcPool::new(SlabPool::new());
+        let a: u8 = pool.insert("a".to_string());
+        pool.retain(a);
+        pool.delete(a);
+        assert_eq!(pool.release(a).as_deref(), Some("a"));
+    }
+
+    #[test]
+    #[should_panic(expected = "release of key 0 which is not live")]
+    fn rc_pool_double_release() {
+        let mut pool: RcPool<SlabPool<DefaultSlot<u32>, u32>> = RcPool::new(SlabPool::new());
+        let a: u32 = pool.insert(5);
+        assert_eq!(pool.release(a), Some(5));
+        let _ = pool.release::<u32, u32>(a);
+    }
+
+    #[test]
+    #[should_panic(expected = "retain of key 3 which is not live")]
+    fn rc_pool_retain_foreign() {
+        let mut pool: RcPool<SlabPool<DefaultSlot<u32>, u32>> = RcPool::new(SlabPool::new());
+        pool.retain(3u32);
+    }
+
+    #[test]
+    #[should_panic(expected = "reference count overflow")]
+    fn rc_pool_overflow() {
+        let mut pool: RcPool<SlabPool<DefaultSlot<u32>, u32>> = RcPool::new(SlabPool::new());
+        let a: u32 = pool.insert(5);
+        pool.counts[0] = u32::MAX;
+        pool.retain(a);
+    }
+}
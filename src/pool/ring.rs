@@ -0,0 +1,558 @@
+/*!
+A pool of double-ended queues implemented as ring buffers packed into a single buffer
+*/
+use std::iter::Chain;
+
+use super::{
+    container::{
+        array::InsertFromSlice,
+        deque::DequePool,
+        iter::{IterMutPool, IterPool},
+        map::GetIndex,
+        map::GetIndexMut,
+        stack::StackPool,
+        ContainerPool, InsertEmpty, InsertWithCapacity, IsEmptyPool, LenPool,
+    },
+    *,
+};
+
+/// The location and extent of a ring buffer within a [`RingPool`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+struct Ring {
+    /// The offset of the ring's block in the buffer
+    block: u32,
+    /// The capacity of the ring, which is either zero or a power of two
+    cap: u32,
+    /// The offset of the front of the ring within its block
+    head: u32,
+    /// The number of elements in the ring
+    len: u32,
+}
+
+impl Ring {
+    /// Get the offset in the buffer of the element at index `ix` of this ring, which must have nonzero capacity
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn slot(&self, ix: u32) -> usize {
+        (self.block + (self.head.wrapping_add(ix) & (self.cap - 1))) as usize
+    }
+}
+
+/// A pool of double-ended queues of `T`, each a ring buffer within a single shared buffer
+///
+/// Each deque is stored in a block of the buffer with a power-of-two capacity of at least 4, along with a fixed-size entry in a table of rings. A deque's
+/// key is the index of its entry in this table, and so never changes: when a deque runs out of capacity, its elements are moved to a new block of twice the
+/// size and its old block is freed. Free blocks are kept in per-size free lists, and reused before the buffer is grown.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RingPool<T> {
+    data: Vec<T>,
+    rings: Vec<Ring>,
+    free_rings: Vec<u32>,
+    free_blocks: Vec<Vec<u32>>,
+}
+
+impl<T> Default for RingPool<T> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Get the size class of a block of capacity `cap`, which must be a power of two of at least 4
+#[cfg_attr(not(tarpaulin), inline(always))]
+fn size_class(cap: u32) -> usize {
+    (cap.trailing_zeros() - 2) as usize
+}
+
+impl<T> RingPool<T> {
+    /// Create a new, empty pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new() -> RingPool<T> {
+        RingPool {
+            data: Vec::new(),
+            rings: Vec::new(),
+            free_rings: Vec::new(),
+            free_blocks: Vec::new(),
+        }
+    }
+
+    /// Get the total size of the buffer, including free blocks
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn buffer_len(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Get the number of live deques in this pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn len(&self) -> usize {
+        self.rings.len() - self.free_rings.len()
+    }
+
+    /// Check whether this pool has no live deques
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Delete every deque in this pool, invalidating all keys
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn clear(&mut self) {
+        self.data.clear();
+        self.rings.clear();
+        self.free_rings.clear();
+        self.free_blocks.clear();
+    }
+
+    /// Free a block of capacity `cap`, if it is nonzero
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn free_block(&mut self, block: u32, cap: u32) {
+        if cap == 0 {
+            return;
+        }
+        let class = size_class(cap);
+        if class >= self.free_blocks.len() {
+            self.free_blocks.resize_with(class + 1, Vec::new);
+        }
+        self.free_blocks[class].push(block)
+    }
+}
+
+impl<T> RingPool<T>
+where
+    T: Copy + Default,
+{
+    /// Create a new, empty pool with space for `elems` elements in total
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn with_capacity(elems: usize) -> RingPool<T> {
+        RingPool {
+            data: Vec::with_capacity(elems),
+            ..Self::new()
+        }
+    }
+
+    /// Allocate a block of capacity `cap`, which must be a power of two of at least 4
+    fn alloc_block(&mut self, cap: u32) -> Option<u32> {
+        if let Some(block) = self.free_blocks.get_mut(size_class(cap)).and_then(Vec::pop) {
+            return Some(block);
+        }
+        let block = u32::try_from(self.data.len()).ok()?;
+        block.checked_add(cap)?;
+        self.data
+            .resize(self.data.len() + cap as usize, T::default());
+        Some(block)
+    }
+
+    /// Allocate an empty ring with capacity `cap`, which must be zero or a power of two of at least 4
+    fn alloc_ring(&mut self, cap: u32) -> Option<u32> {
+        let key = match self.free_rings.last() {
+            Some(key) => *key,
+            None => u32::try_from(self.rings.len()).ok()?,
+        };
+        let block = if cap == 0 { 0 } else { self.alloc_block(cap)? };
+        if self.free_rings.pop().is_none() {
+            self.rings.push(Ring::default());
+        }
+        self.rings[key as usize] = Ring {
+            block,
+            cap,
+            head: 0,
+            len: 0,
+        };
+        Some(key)
+    }
+
+    /// Ensure the ring with key `key` has space for another element, moving it to a larger block if necessary
+    ///
+    /// Returns `None`, leaving the pool unchanged, if a larger block cannot be allocated
+    fn reserve_one(&mut self, key: u32) -> Option<&mut Ring> {
+        let ring = self.rings[key as usize];
+        if ring.len == ring.cap {
+            let cap = if ring.cap == 0 {
+                4
+            } else {
+                ring.cap.checked_mul(2)?
+            };
+            let block = self.alloc_block(cap)?;
+            for ix in 0..ring.len {
+                self.data[(block + ix) as usize] = self.data[ring.slot(ix)];
+            }
+            self.free_block(ring.block, ring.cap);
+            self.rings[key as usize] = Ring {
+                block,
+                cap,
+                head: 0,
+                len: ring.len,
+            };
+        }
+        Some(&mut self.rings[key as usize])
+    }
+
+    /// Get the elements of the deque with a given key as a pair of slices, which in order contain its elements from front to back
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn as_slices(&self, key: u32) -> (&[T], &[T]) {
+        let ring = self.rings[key as usize];
+        let block = &self.data[ring.block as usize..(ring.block + ring.cap) as usize];
+        let first = ring.len.min(ring.cap - ring.head) as usize;
+        let head = ring.head as usize;
+        (
+            &block[head..head + first],
+            &block[..ring.len as usize - first],
+        )
+    }
+
+    /// Get the elements of the deque with a given key as a pair of mutable slices, which in order contain its elements from front to back
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn as_mut_slices(&mut self, key: u32) -> (&mut [T], &mut [T]) {
+        let ring = self.rings[key as usize];
+        let block = &mut self.data[ring.block as usize..(ring.block + ring.cap) as usize];
+        let first = ring.len.min(ring.cap - ring.head) as usize;
+        let (back, front) = block.split_at_mut(ring.head as usize);
+        (&mut front[..first], &mut back[..ring.len as usize - first])
+    }
+
+    /// Get the ring with a given key, or `None` if the key is invalid
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn ring(&self, key: u32) -> Option<&Ring> {
+        self.rings.get(key as usize)
+    }
+}
+
+impl<T> Pool<u32> for RingPool<T> {
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn delete(&mut self, key: u32) {
+        let ring = std::mem::take(&mut self.rings[key as usize]);
+        self.free_block(ring.block, ring.cap);
+        self.free_rings.push(key);
+    }
+}
+
+impl<T> ContainerPool<u32> for RingPool<T> {
+    type Elem = T;
+}
+
+impl<T> IsEmptyPool<u32> for RingPool<T> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn key_is_empty(&self, key: u32) -> bool {
+        self.rings[key as usize].len == 0
+    }
+}
+
+impl<T> LenPool<u32> for RingPool<T> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn key_len(&self, key: u32) -> usize {
+        self.rings[key as usize].len as usize
+    }
+}
+
+impl<T> GetIndex<u32, usize, T> for RingPool<T>
+where
+    T: Copy + Default,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn get_index(&self, key: u32, elem: usize) -> Option<&T> {
+        let ring = self.ring(key)?;
+        if elem >= ring.len as usize {
+            return None;
+        }
+        self.data.get(ring.slot(elem as u32))
+    }
+}
+
+impl<T> GetIndexMut<u32, usize, T> for RingPool<T>
+where
+    T: Copy + Default,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn get_index_mut(&mut self, key: u32, elem: usize) -> Option<&mut T> {
+        let ring = *self.ring(key)?;
+        if elem >= ring.len as usize {
+            return None;
+        }
+        self.data.get_mut(ring.slot(elem as u32))
+    }
+}
+
+impl<T> IterPool<u32> for RingPool<T>
+where
+    T: Copy + Default,
+{
+    type Iter<'a>
+        = Chain<std::slice::Iter<'a, T>, std::slice::Iter<'a, T>>
+    where
+        Self: 'a,
+        T: 'a;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn iter_at(&self, key: u32) -> Self::Iter<'_> {
+        let (front, back) = self.as_slices(key);
+        front.iter().chain(back.iter())
+    }
+}
+
+impl<T> IterMutPool<u32> for RingPool<T>
+where
+    T: Copy + Default,
+{
+    type IterMut<'a>
+        = Chain<std::slice::IterMut<'a, T>, std::slice::IterMut<'a, T>>
+    where
+        Self: 'a,
+        T: 'a;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn iter_at_mut(&mut self, key: u32) -> Self::IterMut<'_> {
+        let (front, back) = self.as_mut_slices(key);
+        front.iter_mut().chain(back.iter_mut())
+    }
+}
+
+impl<T> InsertEmpty<u32> for RingPool<T>
+where
+    T: Copy + Default,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_insert_empty(&mut self) -> Result<u32, ()> {
+        self.alloc_ring(0).ok_or(())
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_unique_empty(&mut self) -> Result<u32, ()> {
+        self.try_insert_empty()
+    }
+}
+
+impl<T> InsertWithCapacity<u32, usize> for RingPool<T>
+where
+    T: Copy + Default,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_insert_with_capacity(&mut self, capacity: usize) -> Result<u32, ()> {
+        let cap = match capacity {
+            0 => 0,
+            capacity => u32::try_from(capacity.max(4).checked_next_power_of_two().ok_or(())?)
+                .map_err(|_| ())?,
+        };
+        self.alloc_ring(cap).ok_or(())
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_unique_with_capacity(&mut self, capacity: usize) -> Result<u32, ()> {
+        self.try_insert_with_capacity(capacity)
+    }
+}
+
+impl<'a, T> Insert<u32, &'a [T]> for RingPool<T>
+where
+    T: Copy + Default,
+{
+    fn try_insert(&mut self, val: &'a [T]) -> Result<u32, &'a [T]> {
+        let Ok(key) = self.try_insert_with_capacity(val.len()) else {
+            return Err(val);
+        };
+        let ring = &mut self.rings[key as usize];
+        ring.len = val.len() as u32;
+        let start = ring.block as usize;
+        self.data[start..start + val.len()].copy_from_slice(val);
+        Ok(key)
+    }
+}
+
+impl<'a, T> InsertFromSlice<'a, u32> for RingPool<T>
+where
+    T: Copy + Default,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_from_slice(&mut self, slice: &'a [T]) -> u32 {
+        self.insert(slice)
+    }
+}
+
+impl<T> StackPool<u32> for RingPool<T>
+where
+    T: Copy + Default,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_into_popped(&mut self, key: u32) -> Result<Option<(u32, T)>, ()> {
+        Ok(self.pop(key).map(|item| (key, item)))
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_into_pushed(&mut self, key: u32, item: T) -> Result<u32, T> {
+        self.try_push(key, item).map(|_| key)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_pop(&mut self, key: u32) -> Result<Option<T>, ()> {
+        let ring = &mut self.rings[key as usize];
+        if ring.len == 0 {
+            return Ok(None);
+        }
+        ring.len -= 1;
+        Ok(Some(self.data[ring.slot(ring.len)]))
+    }
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_push(&mut self, key: u32, item: T) -> Result<(), T> {
+        let Some(ring) = self.reserve_one(key) else {
+            return Err(item);
+        };
+        let slot = ring.slot(ring.len);
+        ring.len += 1;
+        self.data[slot] = item;
+        Ok(())
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn key_capacity(&self, key: u32) -> usize {
+        self.rings[key as usize].cap as usize
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn cleared_key(&mut self, key: u32) -> u32 {
+        self.rings[key as usize].len = 0;
+        key
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn clear_key(&mut self, key: u32) -> Result<(), ()> {
+        self.rings[key as usize].len = 0;
+        Ok(())
+    }
+}
+
+impl<T> DequePool<u32> for RingPool<T>
+where
+    T: Copy + Default,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_into_pushed_front(&mut self, key: u32, item: T) -> Result<u32, T> {
+        self.try_push_front(key, item).map(|_| key)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_into_popped_front(&mut self, key: u32) -> Result<Option<(u32, T)>, ()> {
+        Ok(self.pop_front(key).map(|item| (key, item)))
+    }
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_push_front(&mut self, key: u32, item: T) -> Result<(), T> {
+        let Some(ring) = self.reserve_one(key) else {
+            return Err(item);
+        };
+        ring.head = ring.head.wrapping_sub(1) & (ring.cap - 1);
+        ring.len += 1;
+        let slot = ring.slot(0);
+        self.data[slot] = item;
+        Ok(())
+    }
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_pop_front(&mut self, key: u32) -> Result<Option<T>, ()> {
+        let ring = &mut self.rings[key as usize];
+        if ring.len == 0 {
+            return Ok(None);
+        }
+        let item = self.data[ring.slot(0)];
+        ring.head = (ring.head + 1) & (ring.cap - 1);
+        ring.len -= 1;
+        Ok(Some(item))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::collections::VecDeque;
+
+    use rand::{Rng, SeedableRng};
+    use rand_xoshiro::Xoshiro256StarStar;
+
+    use super::*;
+    use crate::{entity::list::EntityList, pool::container::array::ArrayListPool};
+
+    fn is_array_list_pool<P: ArrayListPool<u32> + DequePool<u32>>(_pool: &P) {}
+
+    #[test]
+    fn basic_ring_pool_usage() {
+        let mut pool: RingPool<u16> = RingPool::new();
+        is_array_list_pool(&pool);
+        let mut v = EntityList::new(&mut pool);
+        assert_eq!(v.pop_front(&mut pool), None);
+        assert_eq!(pool.buffer_len(), 0);
+        v.push_back(2, &mut pool);
+        v.push_front(1, &mut pool);
+        v.push_back(3, &mut pool);
+        v.push_front(0, &mut pool);
+        assert_eq!(pool.buffer_len(), 4);
+        // The ring has wrapped around its block, and must be moved to grow
+        v.push_front(7, &mut pool);
+        assert_eq!(pool.buffer_len(), 12);
+        assert_eq!(v.len(&pool), 5);
+        let elems: Vec<_> = (0..5).map(|ix| *v.get(ix, &pool).unwrap()).collect();
+        assert_eq!(elems, [7, 0, 1, 2, 3]);
+        assert!(v.iter(&pool).eq(&elems));
+        assert_eq!(v.get(5, &pool), None);
+        *v.get_mut(0, &mut pool).unwrap() = 8;
+        assert_eq!(v.pop_front(&mut pool), Some(8));
+        assert_eq!(v.pop_back(&mut pool), Some(3));
+        assert_eq!(v.pop(&mut pool), Some(2));
+        assert_eq!(pool.key_capacity(0), 8);
+
+        // The freed block of size 4 is reused
+        let u = EntityList::from_slice(&[4, 5, 6], &mut pool);
+        assert_eq!(pool.buffer_len(), 12);
+        assert_eq!(u.get(2, &pool), Some(&6));
+        assert_eq!(pool.len(), 2);
+        pool.delete(0);
+        assert_eq!(pool.len(), 1);
+        assert_eq!(pool.insert_with_capacity(5), 0);
+        assert_eq!(pool.key_capacity(0), 8);
+        assert_eq!(pool.buffer_len(), 12);
+        assert!(pool.key_is_empty(0));
+    }
+
+    #[test]
+    fn ring_pool_stress() {
+        let mut rng = Xoshiro256StarStar::from_seed([3; 32]);
+        let mut pool: RingPool<u32> = RingPool::new();
+        let mut deques: Vec<(u32, VecDeque<u32>)> = Vec::new();
+        for i in 0..10000 {
+            let op = rng.gen_range(0..11);
+            if deques.is_empty() || op == 0 {
+                deques.push((pool.insert_empty(), VecDeque::new()));
+                continue;
+            }
+            let ix = rng.gen_range(0..deques.len());
+            let (key, model) = &mut deques[ix];
+            match op {
+                1 => {
+                    pool.delete(*key);
+                    deques.swap_remove(ix);
+                }
+                2 | 3 => {
+                    pool.push_front(*key, i);
+                    model.push_front(i);
+                }
+                4 | 5 => {
+                    pool.push_back(*key, i);
+                    model.push_back(i);
+                }
+                6 | 7 => assert_eq!(pool.pop_front(*key), model.pop_front()),
+                8 => assert_eq!(pool.pop_back(*key), model.pop_back()),
+                9 => {
+                    pool.iter_at_mut(*key).for_each(|elem| *elem += 1);
+                    model.iter_mut().for_each(|elem| *elem += 1);
+                }
+                _ => {
+                    assert_eq!(pool.cleared_key(*key), *key);
+                    model.clear();
+                }
+            }
+            for (key, model) in &deques {
+                assert_eq!(pool.key_len(*key), model.len());
+                assert!(pool.iter_at(*key).eq(model.iter()));
+                for (ix, elem) in model.iter().enumerate() {
+                    assert_eq!(pool.get_index(*key, ix), Some(elem));
+                }
+            }
+        }
+    }
+}
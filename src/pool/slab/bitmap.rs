@@ -0,0 +1,248 @@
+/*!
+A free list backed by a two-level bitmap of free slots
+*/
+use crate::{index::ContiguousIx, slot::RemoveSlot};
+
+use super::{
+    FreeList, FreeListCapacity, NextFreeList, OccupancyList, RemovalList, RollbackList, TrimList,
+    UnlinkList,
+};
+
+const WORD_BITS: usize = u64::BITS as usize;
+
+/// A free list consisting of a two-level bitmap, with a set bit for every free slot
+///
+/// Always returns the lowest free key first. Allocation scans a summary bitmap with one bit per 64 slots, so it takes time linear
+/// in the number of slots divided by 4096, while deallocation and [`FreeListCapacity::len`] take constant time.
+///
+/// Since the bitmap records which slots are free, this free list also implements [`OccupancyList`], which can be used to check whether a
+/// key is live or to iterate over live keys, skipping 64 free slots at a time. Deleting or removing an already-free key is a no-op.
+#[derive(Debug, Clone, Default, Eq, PartialEq, Hash)]
+pub struct BitmapFree {
+    /// Bit `i % 64` of word `i / 64` is set if slot `i` is free
+    words: Vec<u64>,
+    /// Bit `i % 64` of word `i / 64` is set if `words[i]` is nonzero
+    summary: Vec<u64>,
+    /// The number of free slots
+    len: usize,
+}
+
+impl BitmapFree {
+    /// Whether the slot at index `ix` is on this free list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn contains(&self, ix: usize) -> bool {
+        self.words
+            .get(ix / WORD_BITS)
+            .is_some_and(|word| word & (1 << (ix % WORD_BITS)) != 0)
+    }
+
+    /// Get the lowest free index
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn first(&self) -> Option<usize> {
+        let (s, summary) = self
+            .summary
+            .iter()
+            .enumerate()
+            .find(|(_, summary)| **summary != 0)?;
+        let w = s * WORD_BITS + summary.trailing_zeros() as usize;
+        Some(w * WORD_BITS + self.words[w].trailing_zeros() as usize)
+    }
+
+    /// Mark the slot at index `ix` as free, returning whether it was previously in use
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn set(&mut self, ix: usize) -> bool {
+        let w = ix / WORD_BITS;
+        if w >= self.words.len() {
+            self.words.resize(w + 1, 0);
+            self.summary.resize(w / WORD_BITS + 1, 0);
+        }
+        let bit = 1 << (ix % WORD_BITS);
+        if self.words[w] & bit != 0 {
+            return false;
+        }
+        self.words[w] |= bit;
+        self.summary[w / WORD_BITS] |= 1 << (w % WORD_BITS);
+        self.len += 1;
+        true
+    }
+
+    /// Mark the slot at index `ix`, which must be free, as in use
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn unset(&mut self, ix: usize) {
+        let w = ix / WORD_BITS;
+        self.words[w] &= !(1 << (ix % WORD_BITS));
+        if self.words[w] == 0 {
+            self.summary[w / WORD_BITS] &= !(1 << (w % WORD_BITS));
+        }
+        self.len -= 1;
+    }
+}
+
+impl<S, K> FreeList<[S], K> for BitmapFree
+where
+    S: RemoveSlot,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn alloc(&mut self, _buffer: &mut [S]) -> Option<K> {
+        let ix = self.first()?;
+        self.unset(ix);
+        Some(K::new_unchecked(ix))
+    }
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn delete(&mut self, key: K, buffer: &mut [S]) {
+        let ix = key.index();
+        if let Some(slot) = buffer.get_mut(ix) {
+            if !self.contains(ix) {
+                slot.delete_value();
+                self.set(ix);
+            }
+        }
+    }
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn clear(&mut self, _buffer: &mut [S]) {
+        self.words.clear();
+        self.summary.clear();
+        self.len = 0;
+    }
+
+    fn shrink_to_fit(&mut self) {
+        let words = self
+            .words
+            .iter()
+            .rposition(|word| *word != 0)
+            .map_or(0, |w| w + 1);
+        self.words.truncate(words);
+        self.summary.truncate(words.div_ceil(WORD_BITS));
+        self.words.shrink_to_fit();
+        self.summary.shrink_to_fit();
+    }
+}
+
+impl<S, K> TrimList<S, K> for BitmapFree
+where
+    S: RemoveSlot,
+    K: ContiguousIx,
+{
+    fn trim(&mut self, buffer: &mut Vec<S>) {
+        while let Some(last) = buffer.len().checked_sub(1) {
+            if !self.contains(last) {
+                break;
+            }
+            self.unset(last);
+            buffer.pop();
+        }
+    }
+}
+
+impl<S, K> RemovalList<[S], K> for BitmapFree
+where
+    S: RemoveSlot,
+    K: ContiguousIx,
+{
+    type Value = S::Value;
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_remove(&mut self, key: K, buffer: &mut [S]) -> Option<S::Value> {
+        let ix = key.index();
+        if self.contains(ix) {
+            return None;
+        }
+        let value = buffer.get_mut(ix)?.try_remove_value()?;
+        self.set(ix);
+        Some(value)
+    }
+}
+
+impl<S, K> RollbackList<[S], K> for BitmapFree
+where
+    S: RemoveSlot,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn unfree(&mut self, key: K, _buffer: &mut [S]) {
+        if self.contains(key.index()) {
+            self.unset(key.index())
+        }
+    }
+}
+
+impl<S, K> NextFreeList<[S], K> for BitmapFree
+where
+    S: RemoveSlot,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn next_free(&self, _buffer: &[S]) -> Option<K> {
+        self.first().map(K::new_unchecked)
+    }
+}
+
+impl<S, K> FreeListCapacity<[S], K> for BitmapFree
+where
+    S: RemoveSlot,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn len(&self, _buffer: &[S]) -> usize {
+        self.len
+    }
+}
+
+impl<S, K> OccupancyList<[S], K> for BitmapFree
+where
+    S: RemoveSlot,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn is_free(&self, key: K, buffer: &[S]) -> bool {
+        let ix = key.index();
+        ix >= buffer.len() || self.contains(ix)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn next_live(&self, from: usize, buffer: &[S]) -> Option<usize> {
+        let mut w = from / WORD_BITS;
+        let mut mask = u64::MAX << (from % WORD_BITS);
+        while w * WORD_BITS < buffer.len() {
+            let live = !self.words.get(w).copied().unwrap_or(0) & mask;
+            if live != 0 {
+                let ix = w * WORD_BITS + live.trailing_zeros() as usize;
+                return if ix < buffer.len() { Some(ix) } else { None };
+            }
+            w += 1;
+            mask = u64::MAX;
+        }
+        None
+    }
+}
+
+impl<S, K> UnlinkList<S, K> for BitmapFree
+where
+    S: RemoveSlot,
+    S::Value: Default,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn unlink(&mut self, key: K, _buffer: &mut [S]) -> bool {
+        let ix = key.index();
+        if self.contains(ix) {
+            self.unset(ix);
+            true
+        } else {
+            false
+        }
+    }
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn push_free(&mut self, buffer: &mut Vec<S>) -> bool {
+        if K::try_new(buffer.len()).is_none() {
+            return false;
+        }
+        self.set(buffer.len());
+        buffer.push(S::default_value());
+        true
+    }
+}
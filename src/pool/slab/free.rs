@@ -1,9 +1,11 @@
 /*!
 A free list implementation over a buffer of slots
 */
+use std::{cmp::Reverse, collections::BinaryHeap, hash::Hash};
+
 use crate::{
     index::ContiguousIx,
-    slot::{KeySlot, RemoveSlot},
+    slot::{CheckedSlot, KeySlot, RemoveSlot},
 };
 
 /// A free list implementation over a buffer of slots
@@ -21,6 +23,18 @@ pub trait FreeList<B: ?Sized, K> {
 
     /// Clear this free list, resetting it
     fn clear(&mut self, buffer: &mut B);
+
+    /// Reserve space for at least `additional` more slots to be free'd without reallocating
+    ///
+    /// This is a no-op for free lists which do not allocate
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn reserve(&mut self, _additional: usize) {}
+
+    /// Shrink the memory used by this free list as much as possible
+    ///
+    /// This is a no-op for free lists which do not allocate
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn shrink_to_fit(&mut self) {}
 }
 
 /// A free list implementation which allows the removal of values
@@ -58,6 +72,66 @@ pub trait FreeListCapacity<B: ?Sized, K>: FreeList<B, K> {
     fn len(&self, buffer: &B) -> usize;
 }
 
+/// A free list which knows which slots of the buffer are free
+pub trait OccupancyList<B: ?Sized, K>: FreeList<B, K> {
+    /// Whether the slot with the given key is free
+    ///
+    /// Returns `true` for keys past the end of the buffer
+    #[must_use]
+    fn is_free(&self, key: K, buffer: &B) -> bool;
+
+    /// Get the index of the first slot at or after `from` which is not free, if any
+    #[must_use]
+    fn next_live(&self, from: usize, buffer: &B) -> Option<usize>;
+}
+
+/// A free list which supports removing arbitrary slots from the list, and growing the buffer with new free slots
+pub trait UnlinkList<S, K>: FreeList<[S], K> {
+    /// Remove the slot with the given key from the free list, so that it will no longer be returned by `alloc`
+    ///
+    /// Returns `false`, leaving the free list unchanged, if the slot is not on the free list.
+    /// Otherwise, the slot should be considered allocated, and its value set by the caller.
+    #[must_use]
+    fn unlink(&mut self, key: K, buffer: &mut [S]) -> bool;
+
+    /// Append a new free slot to the end of the buffer, putting it on the free list
+    ///
+    /// Returns `false`, leaving the buffer unchanged, if the index of the new slot is not representable as a `K`
+    #[must_use]
+    fn push_free(&mut self, buffer: &mut Vec<S>) -> bool;
+}
+
+/// A free list which supports removing free slots from the end of the buffer
+pub trait TrimList<S, K>: FreeList<[S], K> {
+    /// Remove all free slots from the end of the buffer, as well as from the free list
+    ///
+    /// The relative order in which the remaining free slots are allocated is preserved.
+    fn trim(&mut self, buffer: &mut Vec<S>);
+}
+
+/// A free list which supports undoing the most recent deallocation, as used by [`Transactional`](super::Transactional)
+pub trait RollbackList<B: ?Sized, K>: FreeList<B, K> {
+    /// Undo the most recent call to `delete` or `remove`, which free'd `key`, removing it from the free list
+    ///
+    /// The slot should then be considered allocated, and its value set by the caller.
+    /// If `key` is not the most recently free'd slot still on the free list, the behaviour is unspecified.
+    fn unfree(&mut self, key: K, buffer: &mut B);
+}
+
+/// Get the length of `buffer` with all trailing free slots removed, given an iterator over the free slots
+fn trimmed_len(len: usize, free: impl Iterator<Item = usize>) -> usize {
+    let mut is_free = vec![false; len];
+    for ix in free {
+        if let Some(is_free) = is_free.get_mut(ix) {
+            *is_free = true;
+        }
+    }
+    is_free
+        .iter()
+        .rposition(|free| !free)
+        .map_or(0, |ix| ix + 1)
+}
+
 /// A simple free list consisting of a vector of free keys
 ///
 /// Returns the most recently free'd key first, to maximize caching
@@ -93,6 +167,28 @@ where
     fn clear(&mut self, _buffer: &mut [S]) {
         self.0.clear()
     }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn shrink_to_fit(&mut self) {
+        self.0.shrink_to_fit()
+    }
+}
+
+impl<S, K> TrimList<S, K> for KeyList<K>
+where
+    S: RemoveSlot,
+    K: ContiguousIx,
+{
+    fn trim(&mut self, buffer: &mut Vec<S>) {
+        let len = trimmed_len(buffer.len(), self.0.iter().map(|key| key.index()));
+        self.0.retain(|key| key.index() < len);
+        buffer.truncate(len);
+    }
 }
 
 impl<S, K> RemovalList<[S], K> for KeyList<K>
@@ -115,6 +211,18 @@ where
     }
 }
 
+impl<S, K> RollbackList<[S], K> for KeyList<K>
+where
+    S: RemoveSlot,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn unfree(&mut self, key: K, _buffer: &mut [S]) {
+        let last = self.0.pop();
+        debug_assert!(last == Some(key), "unfree'd key was not the last free'd");
+    }
+}
+
 impl<S, K> NextFreeList<[S], K> for KeyList<K>
 where
     S: RemoveSlot,
@@ -137,6 +245,164 @@ where
     }
 }
 
+/// A free list consisting of a min-heap of free keys
+///
+/// Always returns the lowest free key first, which keeps the live set packed towards the start of the buffer at the cost of
+/// `O(log n)` allocation and deallocation.
+#[derive(Debug, Clone)]
+pub struct MinKeyList<K>(pub BinaryHeap<Reverse<K>>);
+
+impl<K: Ord> Default for MinKeyList<K> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn default() -> Self {
+        Self(BinaryHeap::new())
+    }
+}
+
+impl<K: Ord> MinKeyList<K> {
+    /// Get the free keys in ascending order
+    ///
+    /// Since the layout of a [`BinaryHeap`] depends on the order of insertion, this is used to compare and hash free lists
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn sorted_keys(&self) -> Vec<&K> {
+        let mut keys: Vec<&K> = self.0.iter().map(|key| &key.0).collect();
+        keys.sort_unstable();
+        keys
+    }
+}
+
+impl<K: Ord> PartialEq for MinKeyList<K> {
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn eq(&self, other: &Self) -> bool {
+        self.0.len() == other.0.len() && self.sorted_keys() == other.sorted_keys()
+    }
+}
+
+impl<K: Ord> Eq for MinKeyList<K> {}
+
+impl<K: Ord> PartialOrd for MinKeyList<K> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<K: Ord> Ord for MinKeyList<K> {
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.sorted_keys().cmp(&other.sorted_keys())
+    }
+}
+
+impl<K: Ord + Hash> Hash for MinKeyList<K> {
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.sorted_keys().hash(state)
+    }
+}
+
+impl<S, K> FreeList<[S], K> for MinKeyList<K>
+where
+    S: RemoveSlot,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn alloc(&mut self, _buffer: &mut [S]) -> Option<K> {
+        self.0.pop().map(|Reverse(key)| key)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn delete(&mut self, key: K, buffer: &mut [S]) {
+        if let Some(slot) = buffer.get_mut(key.index()) {
+            slot.delete_value();
+            self.0.push(Reverse(key));
+        }
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn clear(&mut self, _buffer: &mut [S]) {
+        self.0.clear()
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn reserve(&mut self, additional: usize) {
+        self.0.reserve(additional)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn shrink_to_fit(&mut self) {
+        self.0.shrink_to_fit()
+    }
+}
+
+impl<S, K> TrimList<S, K> for MinKeyList<K>
+where
+    S: RemoveSlot,
+    K: ContiguousIx,
+{
+    fn trim(&mut self, buffer: &mut Vec<S>) {
+        let len = trimmed_len(buffer.len(), self.0.iter().map(|key| key.0.index()));
+        self.0.retain(|key| key.0.index() < len);
+        buffer.truncate(len);
+    }
+}
+
+impl<S, K> RemovalList<[S], K> for MinKeyList<K>
+where
+    S: RemoveSlot,
+    K: ContiguousIx,
+{
+    type Value = S::Value;
+
+    fn try_remove(&mut self, key: K, buffer: &mut [S]) -> Option<S::Value> {
+        let value = buffer.get_mut(key.index())?.try_remove_value()?;
+        self.0.push(Reverse(key));
+        Some(value)
+    }
+
+    fn remove(&mut self, key: K, buffer: &mut [S]) -> S::Value {
+        let value = buffer[key.index()].remove_value();
+        self.0.push(Reverse(key));
+        value
+    }
+}
+
+impl<S, K> RollbackList<[S], K> for MinKeyList<K>
+where
+    S: RemoveSlot,
+    K: ContiguousIx,
+{
+    fn unfree(&mut self, key: K, _buffer: &mut [S]) {
+        if self.0.peek() == Some(&Reverse(key)) {
+            self.0.pop();
+        } else {
+            self.0.retain(|free| free.0 != key)
+        }
+    }
+}
+
+impl<S, K> NextFreeList<[S], K> for MinKeyList<K>
+where
+    S: RemoveSlot,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn next_free(&self, _buffer: &[S]) -> Option<K> {
+        self.0.peek().map(|Reverse(key)| *key)
+    }
+}
+
+impl<S, K> FreeListCapacity<[S], K> for MinKeyList<K>
+where
+    S: RemoveSlot,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn len(&self, _buffer: &[S]) -> usize {
+        self.0.len()
+    }
+}
+
 /// An intrusive free list, with keys of type `K`
 #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
 pub struct IntrusiveFree {
@@ -214,6 +480,18 @@ where
     }
 }
 
+impl<S, K> RollbackList<[S], K> for IntrusiveFree
+where
+    S: KeySlot<K>,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn unfree(&mut self, key: K, buffer: &mut [S]) {
+        let head = self.alloc(buffer);
+        debug_assert!(head == Some(key), "unfree'd key was not the last free'd");
+    }
+}
+
 impl<S, K> NextFreeList<[S], K> for IntrusiveFree
 where
     S: KeySlot<K>,
@@ -245,3 +523,265 @@ where
         len
     }
 }
+
+impl<S, K> OccupancyList<[S], K> for IntrusiveFree
+where
+    S: CheckedSlot<K>,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn is_free(&self, key: K, buffer: &[S]) -> bool {
+        !buffer.get(key.index()).is_some_and(|slot| slot.has_value())
+    }
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn next_live(&self, from: usize, buffer: &[S]) -> Option<usize> {
+        (from..buffer.len()).find(|&ix| buffer[ix].has_value())
+    }
+}
+
+impl<S, K> TrimList<S, K> for IntrusiveFree
+where
+    S: KeySlot<K>,
+    K: ContiguousIx,
+{
+    fn trim(&mut self, buffer: &mut Vec<S>) {
+        let mut chain = Vec::new();
+        let mut curr = self.free_head;
+        while let Some(slot) = buffer.get(curr) {
+            chain.push(curr);
+            let next = slot.key().index();
+            if next == curr {
+                break;
+            }
+            curr = next
+        }
+        let len = trimmed_len(buffer.len(), chain.iter().copied());
+        if len == buffer.len() {
+            return;
+        }
+        let kept: Vec<usize> = chain.into_iter().filter(|ix| *ix < len).collect();
+        buffer.truncate(len);
+        self.free_head = kept.first().copied().unwrap_or(usize::MAX);
+        for (i, &ix) in kept.iter().enumerate() {
+            let next = kept.get(i + 1).copied().unwrap_or(ix);
+            buffer[ix].set_key(K::new_unchecked(next));
+        }
+    }
+}
+
+/// An intrusive, doubly linked free list
+///
+/// Free slots store the keys of the previous and next free slots, with a slot's own key standing in for a missing neighbour.
+/// This allows any free slot to be unlinked from the list in constant time.
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct LinkedFree {
+    free_head: usize,
+}
+
+impl Default for LinkedFree {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn default() -> Self {
+        Self {
+            free_head: usize::MAX,
+        }
+    }
+}
+
+impl LinkedFree {
+    /// Set the previous free slot of the slot at `ix`
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn set_prev<S, K>(buffer: &mut [S], ix: usize, prev: K)
+    where
+        S: KeySlot<(K, K)>,
+        K: ContiguousIx,
+    {
+        let (_, next) = buffer[ix].key();
+        buffer[ix].set_key((prev, next))
+    }
+
+    /// Set the next free slot of the slot at `ix`
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn set_next<S, K>(buffer: &mut [S], ix: usize, next: K)
+    where
+        S: KeySlot<(K, K)>,
+        K: ContiguousIx,
+    {
+        let (prev, _) = buffer[ix].key();
+        buffer[ix].set_key((prev, next))
+    }
+
+    /// Get the links for a slot `key` which is about to be pushed to the front of the list
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn push_links<S, K>(&mut self, key: K, buffer: &mut [S]) -> (K, K)
+    where
+        S: KeySlot<(K, K)>,
+        K: ContiguousIx,
+    {
+        let next = if self.free_head < buffer.len() {
+            let next = K::new_unchecked(self.free_head);
+            Self::set_prev(buffer, self.free_head, key);
+            next
+        } else {
+            key
+        };
+        self.free_head = key.index();
+        (key, next)
+    }
+}
+
+impl<S, K> FreeList<[S], K> for LinkedFree
+where
+    S: KeySlot<(K, K)>,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn alloc(&mut self, buffer: &mut [S]) -> Option<K> {
+        let (_, next) = buffer.get(self.free_head)?.key();
+        let old = self.free_head;
+        if next.index() == old {
+            self.free_head = usize::MAX;
+        } else {
+            self.free_head = next.index();
+            Self::set_prev(buffer, next.index(), next);
+        }
+        Some(K::new_unchecked(old))
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn delete(&mut self, key: K, buffer: &mut [S]) {
+        if key.index() < buffer.len() {
+            let links = self.push_links(key, buffer);
+            buffer[key.index()].set_key(links);
+        }
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn clear(&mut self, _buffer: &mut [S]) {
+        self.free_head = usize::MAX
+    }
+}
+
+impl<S, K> RemovalList<[S], K> for LinkedFree
+where
+    S: KeySlot<(K, K)>,
+    K: ContiguousIx,
+{
+    type Value = S::Value;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_remove(&mut self, key: K, buffer: &mut [S]) -> Option<S::Value> {
+        let value = buffer.get_mut(key.index())?.try_swap_key((key, key))?;
+        let links = self.push_links(key, buffer);
+        buffer[key.index()].set_key(links);
+        Some(value)
+    }
+}
+
+impl<S, K> RollbackList<[S], K> for LinkedFree
+where
+    S: KeySlot<(K, K)>,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn unfree(&mut self, key: K, buffer: &mut [S]) {
+        let head = self.alloc(buffer);
+        debug_assert!(head == Some(key), "unfree'd key was not the last free'd");
+    }
+}
+
+impl<S, K> NextFreeList<[S], K> for LinkedFree
+where
+    S: KeySlot<(K, K)>,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn next_free(&self, _buffer: &[S]) -> Option<K> {
+        K::try_new(self.free_head)
+    }
+}
+
+impl<S, K> FreeListCapacity<[S], K> for LinkedFree
+where
+    S: KeySlot<(K, K)>,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn len(&self, buffer: &[S]) -> usize {
+        let mut len = 0;
+        let mut curr = self.free_head;
+        while let Some(slot) = buffer.get(curr) {
+            len += 1;
+            let (_, next) = slot.key();
+            if next.index() == curr {
+                break;
+            }
+            curr = next.index()
+        }
+        len
+    }
+}
+
+impl<S, K> UnlinkList<S, K> for LinkedFree
+where
+    S: CheckedSlot<(K, K)>,
+    K: ContiguousIx,
+{
+    fn unlink(&mut self, key: K, buffer: &mut [S]) -> bool {
+        let ix = key.index();
+        let Some((prev, next)) = buffer.get(ix).and_then(|slot| slot.try_key()) else {
+            return false;
+        };
+        let (is_head, is_tail) = (prev.index() == ix, next.index() == ix);
+        if is_head {
+            self.free_head = if is_tail { usize::MAX } else { next.index() };
+        } else {
+            Self::set_next(buffer, prev.index(), if is_tail { prev } else { next });
+        }
+        if !is_tail {
+            Self::set_prev(buffer, next.index(), if is_head { next } else { prev });
+        }
+        true
+    }
+
+    fn push_free(&mut self, buffer: &mut Vec<S>) -> bool {
+        let Some(key) = K::try_new(buffer.len()) else {
+            return false;
+        };
+        buffer.push(S::from_key((key, key)));
+        let links = self.push_links(key, buffer);
+        buffer[key.index()].set_key(links);
+        true
+    }
+}
+
+impl<S, K> OccupancyList<[S], K> for LinkedFree
+where
+    S: CheckedSlot<(K, K)>,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn is_free(&self, key: K, buffer: &[S]) -> bool {
+        !buffer.get(key.index()).is_some_and(|slot| slot.has_value())
+    }
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn next_live(&self, from: usize, buffer: &[S]) -> Option<usize> {
+        (from..buffer.len()).find(|&ix| buffer[ix].has_value())
+    }
+}
+
+impl<S, K> TrimList<S, K> for LinkedFree
+where
+    S: CheckedSlot<(K, K)>,
+    K: ContiguousIx,
+{
+    fn trim(&mut self, buffer: &mut Vec<S>) {
+        while let Some(last) = buffer.len().checked_sub(1) {
+            if !self.unlink(K::new_unchecked(last), buffer) {
+                break;
+            }
+            buffer.pop();
+        }
+    }
+}
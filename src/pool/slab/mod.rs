@@ -7,17 +7,22 @@ use std::{
 };
 
 use crate::{
-    index::ContiguousIx,
-    slot::{InitFrom, Slot, SlotMut, SlotRef},
+    index::{ContiguousIx, TrustedIx},
+    slot::{InitFrom, RemoveSlot, Slot, SlotMut, SlotRef},
 };
 
 use super::{
+    brand::{GetUnchecked, GetUncheckedMut},
     container::{array::InsertFromSlice, Container, InsertEmpty, InsertWithCapacity, WithCapacity},
-    GetMut, GetRef, Insert, ObjectPool, Pool, Take,
+    DoubleFreePool, DoubleRemovePool, GetMut, GetRef, Insert, ObjectPool, Pool, SafeFreePool, Take,
 };
 
+mod bitmap;
 mod free;
+mod transaction;
+pub use bitmap::*;
 pub use free::*;
+pub use transaction::*;
 
 /// A simple slab allocator supporting recycling of objects with a free-list
 ///
@@ -101,14 +106,20 @@ where
         self.pool.get_mut(key.index())
     }
 
-    // /// Create a new, empty pool with the given capacity
-    // #[cfg_attr(not(tarpaulin), inline)]
-    // pub fn with_capacity(capacity: usize, free_capacity: usize) -> SlabPool<S, K> {
-    //     SlabPool {
-    //         pool: Vec::with_capacity(capacity),
-    //         free_list: Vec::with_capacity(free_capacity),
-    //     }
-    // }
+    /// Create a new, empty pool with space for `capacity` slots, and for `free_capacity` slots to be free'd
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn with_capacity(capacity: usize, free_capacity: usize) -> SlabPool<S, K, F>
+    where
+        F: Default,
+    {
+        let mut free_list = F::default();
+        free_list.reserve(free_capacity);
+        SlabPool {
+            pool: Vec::with_capacity(capacity),
+            free_list,
+            key_type: PhantomData,
+        }
+    }
 
     /// Get the total capacity of this pool
     #[cfg_attr(not(tarpaulin), inline)]
@@ -155,17 +166,28 @@ where
         self.pool.reserve(additional)
     }
 
-    // /// Reserves capacity for at least `additional` more elements to be free'd
-    // #[cfg_attr(not(tarpaulin), inline)]
-    // pub fn reserve_free(&mut self, additional: usize) {
-    //     self.free_list.reserve(additional)
-    // }
+    /// Reserves capacity for at least `additional` more elements to be free'd
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn reserve_free(&mut self, additional: usize) {
+        self.free_list.reserve(additional)
+    }
 
     /// Shrink this pool's capacity as much as possible without changing any indices
     #[cfg_attr(not(tarpaulin), inline)]
     pub fn shrink_to_fit(&mut self) {
         self.pool.shrink_to_fit();
-        // self.free_list.shrink_to_fit();
+        self.free_list.shrink_to_fit();
+    }
+
+    /// Remove all free slots from the end of this pool, without changing any live indices
+    ///
+    /// Note this does not release any memory; to do so, call [`SlabPool::shrink_to_fit`] afterwards
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn trim(&mut self)
+    where
+        F: TrimList<S, K>,
+    {
+        self.free_list.trim(&mut self.pool)
     }
 
     /// Get the key that will be assigned to the next inserted value, or `None` if inserting a new value would cause the pool to overflow
@@ -179,6 +201,114 @@ where
         }
         K::try_new(self.pool.len())
     }
+
+    /// Insert `value` at the given key, growing the pool with free slots as needed
+    ///
+    /// If `key` was free, it is removed from the free list and `Ok(None)` is returned; if it was live, its value is replaced and the
+    /// old value is returned. Returns `Err(value)`, leaving the pool unchanged, if the free list fails to grow the pool.
+    pub fn insert_at(&mut self, key: K, value: S::Value) -> Result<Option<S::Value>, S::Value>
+    where
+        F: UnlinkList<S, K>,
+    {
+        let ix = key.index();
+        let len = self.pool.len();
+        while self.pool.len() <= ix {
+            if !self.free_list.push_free(&mut self.pool) {
+                for free in (len..self.pool.len()).rev() {
+                    let _ = self
+                        .free_list
+                        .unlink(K::new_unchecked(free), &mut self.pool);
+                }
+                self.pool.truncate(len);
+                return Err(value);
+            }
+        }
+        if self.free_list.unlink(key, &mut self.pool) {
+            self.pool[ix].set_value(value);
+            Ok(None)
+        } else {
+            Ok(self.pool[ix].try_swap_value(value))
+        }
+    }
+
+    /// Whether `key` corresponds to a live entry in this pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn contains_key(&self, key: K) -> bool
+    where
+        F: OccupancyList<[S], K>,
+    {
+        !self.free_list.is_free(key, &self.pool)
+    }
+
+    /// Iterate over the keys of all live entries in this pool, in increasing order
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn keys(&self) -> Keys<'_, S, K, F>
+    where
+        F: OccupancyList<[S], K>,
+    {
+        Keys { pool: self, ix: 0 }
+    }
+
+    /// Iterate over the keys and values of all live entries in this pool, in increasing order of key
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn iter(&self) -> Iter<'_, S, K, F>
+    where
+        S: SlotRef,
+        F: OccupancyList<[S], K>,
+    {
+        Iter(self.keys())
+    }
+}
+
+/// An iterator over the live keys of a [`SlabPool`], created by [`SlabPool::keys`]
+#[derive(Debug)]
+pub struct Keys<'a, S, K, F> {
+    pool: &'a SlabPool<S, K, F>,
+    ix: usize,
+}
+
+impl<'a, S, K, F> Clone for Keys<'a, S, K, F> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn clone(&self) -> Self {
+        Keys {
+            pool: self.pool,
+            ix: self.ix,
+        }
+    }
+}
+
+impl<'a, S, K, F> Iterator for Keys<'a, S, K, F>
+where
+    K: ContiguousIx,
+    F: OccupancyList<[S], K>,
+{
+    type Item = K;
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn next(&mut self) -> Option<K> {
+        let ix = self.pool.free_list.next_live(self.ix, &self.pool.pool)?;
+        self.ix = ix + 1;
+        Some(K::new_unchecked(ix))
+    }
+}
+
+/// An iterator over the live entries of a [`SlabPool`], created by [`SlabPool::iter`]
+#[derive(Debug, Clone)]
+pub struct Iter<'a, S, K, F>(Keys<'a, S, K, F>);
+
+impl<'a, S, K, F> Iterator for Iter<'a, S, K, F>
+where
+    S: SlotRef,
+    K: ContiguousIx,
+    F: OccupancyList<[S], K>,
+{
+    type Item = (K, &'a S::Value);
+
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn next(&mut self) -> Option<Self::Item> {
+        let key = self.0.next()?;
+        Some((key, self.0.pool.pool[key.index()].value()))
+    }
 }
 
 impl<S, K, V, F> Insert<K, V> for SlabPool<S, K, F>
@@ -348,8 +478,64 @@ where
     }
 }
 
+// SAFETY: a key returned by an insertion was in bounds when inserted, since it was either created by `K::try_new` on the length of the
+// pool or used to index the pool, and as `K: TrustedIx` it still has the same index. A slab pool only shrinks when cleared or trimmed,
+// neither of which is possible through the traits allowed by `GetUnchecked`
+unsafe impl<S, K, F> GetUnchecked<K, S::Value> for SlabPool<S, K, F>
+where
+    S: SlotRef,
+    K: TrustedIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    unsafe fn at_unchecked(&self, key: K) -> &S::Value {
+        self.pool.get_unchecked(key.index()).value()
+    }
+}
+
+// SAFETY: as for `GetUnchecked`
+unsafe impl<S, K, F> GetUncheckedMut<K, S::Value> for SlabPool<S, K, F>
+where
+    S: SlotMut,
+    K: TrustedIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    unsafe fn at_unchecked_mut(&mut self, key: K) -> &mut S::Value {
+        self.pool.get_unchecked_mut(key.index()).value_mut()
+    }
+}
+
 pub type KeySlabPool<S, K = usize> = SlabPool<S, K, IntrusiveFree>;
 
+/// A [`SlabPool`] which always reuses the lowest free key first
+pub type MinSlabPool<S, K = usize> = SlabPool<S, K, MinKeyList<K>>;
+
+/// A [`SlabPool`] using a doubly-linked [`LinkedFree`] free list, supporting [`SlabPool::insert_at`]
+pub type LinkedSlabPool<S, K = usize> = SlabPool<S, K, LinkedFree>;
+
+/// A [`SlabPool`] which tracks which keys are live using a [`BitmapFree`]
+pub type BitmapSlabPool<S, K = usize> = SlabPool<S, K, BitmapFree>;
+
+impl<S, K> SafeFreePool<K> for SlabPool<S, K, BitmapFree>
+where
+    S: RemoveSlot,
+    K: ContiguousIx,
+{
+}
+
+impl<S, K> DoubleFreePool<K> for SlabPool<S, K, BitmapFree>
+where
+    S: RemoveSlot,
+    K: ContiguousIx,
+{
+}
+
+impl<S, K> DoubleRemovePool<K> for SlabPool<S, K, BitmapFree>
+where
+    S: RemoveSlot,
+    K: ContiguousIx,
+{
+}
+
 #[cfg(test)]
 mod test {
     use crate::pool::container::map::{GetIndex, GetIndexMut};
@@ -726,6 +912,279 @@ mod test {
         }
     }
 
+    #[test]
+    fn min_slab_pool_insertion_removal_stress() {
+        const REMOVAL_FRACTION: f64 = 0.3;
+        const SIZE: usize = 1000;
+        let mut rng = Xoshiro256StarStar::from_seed([0xAB; 32]);
+        let mut pool: MinSlabPool<DefaultSlot<usize>> = MinSlabPool::new();
+        let mut live = std::collections::BTreeMap::new();
+        for i in 0..SIZE {
+            if !live.is_empty() && rng.gen_bool(REMOVAL_FRACTION) {
+                let key = *live.keys().nth(rng.gen_range(0..live.len())).unwrap();
+                assert_eq!(live.remove(&key), pool.try_remove(key));
+            } else {
+                let expected = (0..).find(|k| !live.contains_key(k)).unwrap();
+                assert_eq!(pool.next_key(), Some(expected));
+                let key = pool.insert(i);
+                assert_eq!(key, expected);
+                live.insert(key, i);
+            }
+            assert_eq!(pool.free_slots(), pool.total_slots() - live.len());
+        }
+    }
+
+    #[test]
+    fn min_slab_pool_reuses_lowest_key() {
+        let mut pool: MinSlabPool<DefaultSlot<u32>, u8> = MinSlabPool::new();
+        for i in 0..8 {
+            assert_eq!(pool.insert(i), i as u8);
+        }
+        pool.delete(5);
+        pool.delete(2);
+        assert_eq!(pool.remove(6), 6);
+        assert_eq!(pool.free_slots(), 3);
+        assert_eq!(pool.next_key(), Some(2));
+        assert_eq!(pool.insert(10), 2);
+        assert_eq!(pool.insert(11), 5);
+        assert_eq!(pool.insert(12), 6);
+        assert_eq!(pool.insert(13), 8);
+        assert_eq!(pool.free_slots(), 0);
+        pool.clear();
+        assert_eq!(pool.free_slots(), 0);
+        assert_eq!(pool.insert(0), 0);
+
+        // Free lists compare by their set of free keys, not the layout of the heap
+        let mut a: MinSlabPool<DefaultSlot<u32>, u8> = MinSlabPool::new();
+        let mut b = a.clone();
+        for i in 0..4 {
+            assert_eq!(a.insert(i), i as u8);
+            assert_eq!(b.insert(i), i as u8);
+        }
+        a.delete(1);
+        a.delete(3);
+        a.delete(0);
+        b.delete(3);
+        b.delete(0);
+        b.delete(1);
+        assert_eq!(a, b);
+        assert_eq!(
+            std::collections::HashSet::from([a.clone(), b.clone()]).len(),
+            1
+        );
+        assert_eq!(a.insert(5), 0);
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn bitmap_slab_pool_occupancy() {
+        let mut pool: BitmapSlabPool<DefaultSlot<u32>, u16> = BitmapSlabPool::new();
+        assert_eq!(pool.keys().next(), None);
+        assert!(!pool.contains_key(0));
+        for i in 0..300 {
+            assert_eq!(pool.insert(i), i as u16);
+        }
+        assert!(pool.keys().eq(0..300));
+        // Free everything except a few keys, spread over several words
+        for i in 0..300 {
+            if i != 3 && i != 64 && i != 200 && i != 299 {
+                pool.delete(i);
+            }
+        }
+        assert_eq!(pool.free_slots(), 296);
+        assert!(pool.keys().eq([3, 64, 200, 299]));
+        assert!(pool
+            .iter()
+            .eq([(3, &3), (64, &64), (200, &200), (299, &299)]));
+        assert!(pool.contains_key(64));
+        assert!(!pool.contains_key(65));
+        assert!(!pool.contains_key(300));
+
+        // Double frees and removes are harmless
+        pool.delete(65);
+        assert_eq!(pool.try_remove(65), None);
+        assert_eq!(pool.free_slots(), 296);
+        assert_eq!(pool.remove(64), 64);
+        pool.delete(64);
+        assert_eq!(pool.free_slots(), 297);
+
+        // Keys are reused lowest first
+        assert_eq!(pool.next_key(), Some(0));
+        assert_eq!(pool.insert(7), 0);
+        assert_eq!(pool.insert(7), 1);
+        assert_eq!(pool.insert(7), 2);
+        assert_eq!(pool.insert(7), 4);
+        assert!(pool.keys().take(5).eq([0, 1, 2, 3, 4]));
+        pool.clear();
+        assert_eq!(pool.keys().next(), None);
+        assert_eq!(pool.free_slots(), 0);
+        assert_eq!(pool.insert(1), 0);
+    }
+
+    #[test]
+    fn bitmap_slab_pool_stress() {
+        let mut rng = Xoshiro256StarStar::from_seed([0xCD; 32]);
+        let mut pool: BitmapSlabPool<DefaultSlot<usize>> = BitmapSlabPool::new();
+        let mut live = std::collections::BTreeMap::new();
+        for i in 0..4000 {
+            if !live.is_empty() && rng.gen_bool(0.45) {
+                let key = rng.gen_range(0..pool.total_slots());
+                assert_eq!(pool.try_remove(key), live.remove(&key));
+            } else {
+                let key = pool.insert(i);
+                assert_eq!(key, (0..).find(|k| !live.contains_key(k)).unwrap());
+                live.insert(key, i);
+            }
+        }
+        assert_eq!(pool.free_slots(), pool.total_slots() - live.len());
+        assert!(pool.iter().eq(live.iter().map(|(k, v)| (*k, v))));
+    }
+
+    #[test]
+    fn linked_slab_pool_insert_at() {
+        let mut pool: LinkedSlabPool<Either<(u8, u8), String>, u8> = LinkedSlabPool::new();
+        assert_eq!(pool.insert_at(3, "3".to_string()), Ok(None));
+        assert_eq!(pool.total_slots(), 4);
+        assert_eq!(pool.free_slots(), 3);
+        assert_eq!(pool.at(3), "3");
+        assert_eq!(pool.insert_at(1, "1".to_string()), Ok(None));
+        assert_eq!(pool.free_slots(), 2);
+        assert_eq!(
+            pool.insert_at(3, "three".to_string()),
+            Ok(Some("3".to_string()))
+        );
+        assert_eq!(pool.at(3), "three");
+        assert_eq!(pool.insert_at(2, "2".to_string()), Ok(None));
+        assert_eq!(pool.free_slots(), 1);
+        assert_eq!(pool.next_key(), Some(0));
+        assert_eq!(pool.insert("0".to_string()), 0);
+        assert_eq!(pool.free_slots(), 0);
+        assert_eq!(pool.insert("4".to_string()), 4);
+        assert_eq!(pool.remove(2), "2");
+        pool.delete(0);
+        assert_eq!(pool.free_slots(), 2);
+        assert_eq!(pool.insert_at(2, "two".to_string()), Ok(None));
+        assert_eq!(pool.free_slots(), 1);
+        assert_eq!(pool.insert("zero".to_string()), 0);
+        for (i, s) in ["zero", "1", "two", "three", "4"].iter().enumerate() {
+            assert_eq!(pool.at(i as u8), s);
+        }
+        assert_eq!(pool.insert_at(255, "255".to_string()), Ok(None));
+        assert_eq!(pool.free_slots(), 250);
+        assert_eq!(
+            pool.try_insert("256".to_string()).map(|k| k < 255),
+            Ok(true)
+        );
+    }
+
+    #[test]
+    fn unlink_list_stress() {
+        fn check<F, S>(seed: u8)
+        where
+            F: UnlinkList<S, usize>
+                + RemovalList<[S], usize, Value = usize>
+                + FreeListCapacity<[S], usize>
+                + Default,
+            S: SlotRef<Value = usize>,
+        {
+            let mut rng = Xoshiro256StarStar::from_seed([seed; 32]);
+            let mut pool: SlabPool<S, usize, F> = SlabPool::new();
+            let mut live = std::collections::BTreeMap::new();
+            for i in 0..2000 {
+                let r = rng.gen_range(0..10);
+                if r < 3 && !live.is_empty() {
+                    let key = *live.keys().nth(rng.gen_range(0..live.len())).unwrap();
+                    assert_eq!(pool.try_remove(key), live.remove(&key));
+                } else if r < 6 {
+                    let key = rng.gen_range(0..pool.total_slots() + 10);
+                    assert_eq!(pool.insert_at(key, i), Ok(live.insert(key, i)));
+                } else {
+                    let key = pool.insert(i);
+                    assert_eq!(live.insert(key, i), None);
+                }
+                assert_eq!(pool.free_slots(), pool.total_slots() - live.len());
+            }
+            for (key, value) in live {
+                assert_eq!(pool.at(key), &value);
+            }
+        }
+        check::<LinkedFree, Either<(usize, usize), usize>>(0x12);
+        check::<BitmapFree, DefaultSlot<usize>>(0x34);
+    }
+
+    #[test]
+    fn slab_pool_trim() {
+        fn check<F, S>()
+        where
+            F: TrimList<S, u8>
+                + RemovalList<[S], u8, Value = u32>
+                + FreeListCapacity<[S], u8>
+                + Default,
+            S: SlotRef<Value = u32>,
+        {
+            let mut pool: SlabPool<S, u8, F> = SlabPool::with_capacity(10, 5);
+            assert!(pool.capacity() >= 10);
+            pool.trim();
+            assert_eq!(pool.total_slots(), 0);
+            for i in 0..10 {
+                assert_eq!(pool.insert(i), i as u8);
+            }
+            pool.trim();
+            assert_eq!(pool.total_slots(), 10);
+            for i in [8, 2, 9, 5, 7] {
+                assert_eq!(pool.remove(i), i as u32);
+            }
+            pool.trim();
+            assert_eq!(pool.total_slots(), 7);
+            assert_eq!(pool.free_slots(), 2);
+            for i in [0, 1, 3, 4, 6] {
+                assert_eq!(pool.at(i), &(i as u32));
+            }
+            let mut reused = [pool.insert(20), pool.insert(21)];
+            reused.sort();
+            assert_eq!(reused, [2, 5]);
+            assert_eq!(pool.insert(22), 7);
+            for i in 0..8 {
+                assert!(pool.try_remove(i).is_some());
+            }
+            pool.trim();
+            assert_eq!(pool.total_slots(), 0);
+            assert_eq!(pool.free_slots(), 0);
+            pool.shrink_to_fit();
+            assert_eq!(pool.capacity(), 0);
+            assert_eq!(pool.insert(5), 0);
+        }
+        check::<KeyList<u8>, DefaultSlot<u32>>();
+        check::<MinKeyList<u8>, DefaultSlot<u32>>();
+        check::<BitmapFree, DefaultSlot<u32>>();
+        check::<IntrusiveFree, Either<u8, u32>>();
+        check::<LinkedFree, Either<(u8, u8), u32>>();
+    }
+
+    #[test]
+    fn slab_pool_trim_stress() {
+        let mut rng = Xoshiro256StarStar::from_seed([0x56; 32]);
+        let mut pool: KeySlabPool<Either<usize, usize>> = KeySlabPool::new();
+        let mut live = std::collections::BTreeMap::new();
+        for i in 0..2000 {
+            if rng.gen_bool(0.02) {
+                pool.trim();
+                let len = live.keys().next_back().map_or(0, |k| k + 1);
+                assert_eq!(pool.total_slots(), len);
+            } else if !live.is_empty() && rng.gen_bool(0.4) {
+                let key = *live.keys().nth(rng.gen_range(0..live.len())).unwrap();
+                assert_eq!(pool.try_remove(key), live.remove(&key));
+            } else {
+                let key = pool.insert(i);
+                assert_eq!(live.insert(key, i), None);
+            }
+            assert_eq!(pool.free_slots(), pool.total_slots() - live.len());
+        }
+        for (key, value) in live {
+            assert_eq!(pool.at(key), &value);
+        }
+    }
+
     #[test]
     fn slab_stack_pool() {
         let mut pool: SlabPool<DefaultSlot<Vec<u32>>> = SlabPool::new();
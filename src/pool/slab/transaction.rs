@@ -0,0 +1,350 @@
+/*!
+A wrapper around a slab which journals modifications, allowing them to be rolled back
+*/
+use crate::{
+    index::ContiguousIx,
+    slot::{InitFrom, Slot, SlotMut, SlotRef},
+};
+
+use super::*;
+
+/// A checkpoint in the journal of a [`Transactional`] pool, which can be rolled back to
+///
+/// Returned by [`Transactional::checkpoint`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Checkpoint(usize);
+
+/// An entry in the journal of a [`Transactional`] pool
+#[derive(Debug, Clone, Eq, PartialEq, Hash)]
+enum Entry<K, V> {
+    /// `key` was inserted, either by reusing a free slot or by appending a new slot
+    Insert { key: K, appended: bool },
+    /// `key` was removed, having had value `value`
+    Remove { key: K, value: V },
+    /// `key` was mutably borrowed, having had value `value`
+    Write { key: K, value: V },
+}
+
+mod sealed {
+    /// Prevents [`Journaled`](super::Journaled) from being implemented outside this module
+    pub trait Sealed {}
+}
+
+/// A pool whose modifications can be journaled by a [`Transactional`] wrapper
+///
+/// This trait is sealed, and only implemented for [`SlabPool`].
+pub trait Journaled: sealed::Sealed {
+    /// The type of keys into this pool
+    type Key;
+    /// The type of values stored in this pool
+    type Value;
+}
+
+impl<S: Slot, K, F> sealed::Sealed for SlabPool<S, K, F> {}
+
+impl<S: Slot, K, F> Journaled for SlabPool<S, K, F> {
+    type Key = K;
+    type Value = S::Value;
+}
+
+/// A wrapper around a [`SlabPool`] which journals every `insert`, `take`, `delete` and `at_mut`, so that they can be undone
+///
+/// [`Transactional::checkpoint`] returns a token which [`Transactional::rollback`] can later use to restore exactly the key-to-value
+/// mapping and free list state at the time the checkpoint was taken, at a cost proportional to the number of operations since.
+/// [`Transactional::commit`] discards the journal, invalidating all checkpoints.
+///
+/// Note that every call to `at_mut`, `try_at_mut`, `take` or `try_take` clones the value into the journal, whereas `delete` moves it.
+#[derive(Debug, Clone)]
+pub struct Transactional<P: Journaled> {
+    pool: P,
+    journal: Vec<Entry<P::Key, P::Value>>,
+}
+
+impl<S, K, F> Transactional<SlabPool<S, K, F>>
+where
+    S: Slot,
+    K: ContiguousIx,
+    F: RollbackList<[S], K>,
+{
+    /// Wrap a pool, starting with an empty journal
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new(pool: SlabPool<S, K, F>) -> Self {
+        Transactional {
+            pool,
+            journal: Vec::new(),
+        }
+    }
+
+    /// Get a reference to the underlying pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn inner(&self) -> &SlabPool<S, K, F> {
+        &self.pool
+    }
+
+    /// Commit all changes and unwrap this pool, returning the underlying pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn into_inner(self) -> SlabPool<S, K, F> {
+        self.pool
+    }
+
+    /// Get the number of entries in the journal
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn journal_len(&self) -> usize {
+        self.journal.len()
+    }
+
+    /// Get a checkpoint for the current state of the pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.journal.len())
+    }
+
+    /// Discard the journal, making all changes so far permanent
+    ///
+    /// All previously returned checkpoints are invalidated.
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn commit(&mut self) {
+        self.journal.clear()
+    }
+
+    /// Undo every change made since `checkpoint` was taken
+    ///
+    /// Checkpoints taken after `checkpoint` are invalidated, while checkpoints taken before it remain valid.
+    /// Panics if `checkpoint` has been invalidated by a previous rollback or commit.
+    pub fn rollback(&mut self, checkpoint: Checkpoint)
+    where
+        F: RemovalList<[S], K, Value = S::Value>,
+    {
+        assert!(
+            checkpoint.0 <= self.journal.len(),
+            "checkpoint has been invalidated by a rollback or commit"
+        );
+        while self.journal.len() > checkpoint.0 {
+            match self.journal.pop().unwrap() {
+                Entry::Insert { key, appended } => {
+                    if appended {
+                        debug_assert_eq!(key.index() + 1, self.pool.pool.len());
+                        self.pool.pool.pop();
+                    } else {
+                        self.pool.free_list.delete(key, &mut self.pool.pool);
+                    }
+                }
+                Entry::Remove { key, value } => {
+                    self.pool.free_list.unfree(key, &mut self.pool.pool);
+                    self.pool.pool[key.index()].set_value(value);
+                }
+                Entry::Write { key, value } => self.pool.pool[key.index()].set_value(value),
+            }
+        }
+    }
+}
+
+impl<S, K, F, V> Insert<K, V> for Transactional<SlabPool<S, K, F>>
+where
+    S: Slot + InitFrom<V>,
+    K: ContiguousIx,
+    F: FreeList<[S], K>,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_insert(&mut self, val: V) -> Result<K, V> {
+        let len = self.pool.total_slots();
+        let key = self.pool.try_insert(val)?;
+        self.journal.push(Entry::Insert {
+            key,
+            appended: key.index() == len,
+        });
+        Ok(key)
+    }
+}
+
+impl<S, K, F> Pool<K> for Transactional<SlabPool<S, K, F>>
+where
+    S: Slot,
+    K: ContiguousIx,
+    F: RemovalList<[S], K, Value = S::Value>,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn delete(&mut self, key: K) {
+        if let Some(value) = self.pool.try_take(key) {
+            self.journal.push(Entry::Remove { key, value });
+        }
+    }
+}
+
+impl<S, K, F> ObjectPool<K> for Transactional<SlabPool<S, K, F>>
+where
+    S: Slot,
+    K: ContiguousIx,
+    F: RemovalList<[S], K, Value = S::Value>,
+{
+    type Object = S::Value;
+}
+
+impl<S, K, F> Take<K, S::Value> for Transactional<SlabPool<S, K, F>>
+where
+    S: Slot,
+    S::Value: Clone,
+    K: ContiguousIx,
+    F: RemovalList<[S], K, Value = S::Value>,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_take(&mut self, key: K) -> Option<S::Value> {
+        let value = self.pool.try_take(key)?;
+        self.journal.push(Entry::Remove {
+            key,
+            value: value.clone(),
+        });
+        Some(value)
+    }
+}
+
+impl<S, K, F> GetRef<K, S::Value> for Transactional<SlabPool<S, K, F>>
+where
+    S: SlotRef,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at(&self, key: K) -> Option<&S::Value> {
+        self.pool.try_at(key)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn at(&self, key: K) -> &S::Value {
+        self.pool.at(key)
+    }
+}
+
+impl<S, K, F> GetMut<K, S::Value> for Transactional<SlabPool<S, K, F>>
+where
+    S: SlotMut + SlotRef,
+    S::Value: Clone,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_at_mut(&mut self, key: K) -> Option<&mut S::Value> {
+        let value = self.pool.try_at(key)?.clone();
+        self.journal.push(Entry::Write { key, value });
+        self.pool.try_at_mut(key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pool::RemovePool;
+    use crate::slot::DefaultSlot;
+    use either::Either;
+    use rand::{Rng, SeedableRng};
+    use rand_xoshiro::Xoshiro256StarStar;
+
+    #[test]
+    fn basic_transactional_usage() {
+        let mut pool: Transactional<SlabPool<DefaultSlot<u32>, u8>> =
+            Transactional::new(SlabPool::new());
+        let a: u8 = pool.insert(1);
+        let b: u8 = pool.insert(2);
+        pool.commit();
+        assert_eq!(pool.journal_len(), 0);
+
+        let outer = pool.checkpoint();
+        *pool.at_mut(a) = 3;
+        pool.delete(b);
+        let inner = pool.checkpoint();
+        let c = pool.insert(4);
+        assert_eq!(c, b);
+        assert_eq!(pool.remove(a), 3);
+        assert_eq!(pool.insert(5), a);
+        assert_eq!(pool.insert(6), 2);
+        assert_eq!(pool.journal_len(), 6);
+
+        pool.rollback(inner);
+        assert_eq!(pool.journal_len(), 2);
+        assert_eq!(pool.at(a), &3);
+        assert_eq!(pool.inner().total_slots(), 2);
+        assert_eq!(pool.inner().free_slots(), 1);
+
+        pool.rollback(outer);
+        assert_eq!(pool.at(a), &1);
+        assert_eq!(pool.at(b), &2);
+        assert_eq!(pool.inner().free_slots(), 0);
+        assert_eq!(pool.into_inner().total_slots(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "checkpoint has been invalidated")]
+    fn transactional_rollback_after_commit() {
+        let mut pool: Transactional<SlabPool<DefaultSlot<u32>, u8>> =
+            Transactional::new(SlabPool::new());
+        let _ = pool.insert(1);
+        let checkpoint = pool.checkpoint();
+        let _ = pool.insert(2);
+        pool.commit();
+        pool.rollback(checkpoint);
+    }
+
+    #[test]
+    fn transactional_stress() {
+        fn check<F, S>(seed: u8)
+        where
+            F: RollbackList<[S], u16>
+                + RemovalList<[S], u16, Value = u32>
+                + FreeListCapacity<[S], u16>
+                + Clone
+                + Default,
+            S: SlotMut<Value = u32> + SlotRef + Clone,
+        {
+            let mut rng = Xoshiro256StarStar::from_seed([seed; 32]);
+            let mut pool = Transactional::new(SlabPool::<S, u16, F>::new());
+            let mut live: Vec<u16> = Vec::new();
+            let mut checkpoints = Vec::new();
+            for i in 0..3000 {
+                match rng.gen_range(0..20) {
+                    0..=5 => live.push(pool.insert(i)),
+                    6..=8 if !live.is_empty() => {
+                        let key = live.swap_remove(rng.gen_range(0..live.len()));
+                        pool.delete(key);
+                    }
+                    9..=10 if !live.is_empty() => {
+                        let key = live.swap_remove(rng.gen_range(0..live.len()));
+                        assert!(pool.try_take(key).is_some());
+                    }
+                    11..=13 if !live.is_empty() => {
+                        let key = live[rng.gen_range(0..live.len())];
+                        *pool.at_mut(key) = i;
+                    }
+                    14..=15 => {
+                        checkpoints.push((pool.checkpoint(), pool.inner().clone(), live.clone()))
+                    }
+                    16..=17 if !checkpoints.is_empty() => {
+                        let ix = rng.gen_range(0..checkpoints.len());
+                        checkpoints.truncate(ix + 1);
+                        let (checkpoint, snapshot, snapshot_live) = checkpoints.pop().unwrap();
+                        pool.rollback(checkpoint);
+                        live = snapshot_live;
+                        let mut restored = pool.inner().clone();
+                        let mut snapshot = snapshot;
+                        assert_eq!(restored.total_slots(), snapshot.total_slots());
+                        assert_eq!(restored.free_slots(), snapshot.free_slots());
+                        for ix in 0..snapshot.total_slots() as u16 {
+                            assert_eq!(restored.try_at(ix), snapshot.try_at(ix));
+                        }
+                        // The free list must hand out keys in exactly the same order
+                        for j in 0..snapshot.free_slots() as u32 + 1 {
+                            assert_eq!(restored.insert(j), snapshot.insert(j));
+                        }
+                    }
+                    18 => {
+                        pool.commit();
+                        checkpoints.clear();
+                    }
+                    _ => {}
+                }
+            }
+        }
+        check::<KeyList<u16>, DefaultSlot<u32>>(0x32);
+        check::<MinKeyList<u16>, DefaultSlot<u32>>(0x33);
+        check::<BitmapFree, DefaultSlot<u32>>(0x34);
+        check::<IntrusiveFree, Either<u16, u32>>(0x35);
+        check::<LinkedFree, Either<(u16, u16), u32>>(0x36);
+    }
+}
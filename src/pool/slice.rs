@@ -0,0 +1,650 @@
+/*!
+Arenas packing unsized slices and strings contiguously into a single buffer
+*/
+use std::{marker::PhantomData, ops::Range};
+
+use crate::index::ContiguousIx;
+
+use crate::index::Neg;
+
+use super::{
+    container::{
+        array::{InsertFromSlice, SliceMutPool, SliceRefPool},
+        iter::{IterMutPool, IterPool},
+        map::{GetIndex, GetIndexMut},
+        ContainerPool, InsertEmpty, IsEmptyPool, LenPool,
+    },
+    *,
+};
+
+/// An arena allocator for slices of `T`, packed contiguously into a single buffer
+///
+/// Each slice is stored as a range of a single buffer of `T`, delimited by the offset of its end, of type `O`. Hence, the only per-slice overhead is a
+/// single `O`, and the total number of elements stored in the arena can be at most `O::MAX_INDEX`.
+///
+/// As for an [`Arena`] with [`ByClone`], deletion is a no-op.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct SliceArena<T, K = usize, O = usize> {
+    data: Vec<T>,
+    ends: Vec<O>,
+    key_type: PhantomData<K>,
+}
+
+impl<T, K, O> Default for SliceArena<T, K, O> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, K, O> SliceArena<T, K, O> {
+    /// Create a new, empty arena
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new() -> SliceArena<T, K, O> {
+        SliceArena {
+            data: Vec::new(),
+            ends: Vec::new(),
+            key_type: PhantomData,
+        }
+    }
+
+    /// Create a new, empty arena with space for `slices` slices containing a total of `elems` elements
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn with_capacity(slices: usize, elems: usize) -> SliceArena<T, K, O> {
+        SliceArena {
+            data: Vec::with_capacity(elems),
+            ends: Vec::with_capacity(slices),
+            key_type: PhantomData,
+        }
+    }
+
+    /// Get the number of slices in this arena
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn len(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Check whether this arena contains no slices
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn is_empty(&self) -> bool {
+        self.ends.is_empty()
+    }
+
+    /// Get the total number of elements in all slices in this arena
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn total_elems(&self) -> usize {
+        self.data.len()
+    }
+
+    /// Get every element in this arena, with slices concatenated in order of insertion
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn data(&self) -> &[T] {
+        &self.data
+    }
+}
+
+impl<T, K, O> SliceArena<T, K, O>
+where
+    K: ContiguousIx,
+    O: ContiguousIx,
+{
+    /// Get the range of the buffer occupied by the slice with a given key, or `None` if the key is invalid
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn range(&self, key: K) -> Option<Range<usize>> {
+        let ix = key.index();
+        let end = self.ends.get(ix)?.index();
+        let start = if ix == 0 {
+            0
+        } else {
+            self.ends[ix - 1].index()
+        };
+        Some(start..end)
+    }
+
+    /// Get the slice with a given key, or `None` if the key is invalid
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get(&self, key: K) -> Option<&[T]> {
+        let range = self.range(key)?;
+        Some(&self.data[range])
+    }
+
+    /// Get the slice with a given key mutably, or `None` if the key is invalid
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get_mut(&mut self, key: K) -> Option<&mut [T]> {
+        let range = self.range(key)?;
+        Some(&mut self.data[range])
+    }
+
+    /// Get the key and end offset of a new slice ending at `end`, or `None` if either is not representable
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn next_entry(&self, end: usize) -> Option<(K, O)> {
+        Some((K::try_new(self.ends.len())?, O::try_new(end)?))
+    }
+
+    /// Insert a slice by extending the buffer with the elements of `elems`, returning its key
+    ///
+    /// Returns `None`, leaving the arena unchanged, if either the new key or the new end offset is not representable
+    pub fn try_extend(&mut self, elems: impl IntoIterator<Item = T>) -> Option<K> {
+        K::try_new(self.ends.len())?;
+        let start = self.data.len();
+        self.data.extend(elems);
+        let Some((key, end)) = self.next_entry(self.data.len()) else {
+            self.data.truncate(start);
+            return None;
+        };
+        self.ends.push(end);
+        Some(key)
+    }
+}
+
+impl<'a, T, K, O> Insert<K, &'a [T]> for SliceArena<T, K, O>
+where
+    T: Clone,
+    K: ContiguousIx,
+    O: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_insert(&mut self, val: &'a [T]) -> Result<K, &'a [T]> {
+        let Some((key, end)) = self.next_entry(self.data.len() + val.len()) else {
+            return Err(val);
+        };
+        self.data.extend_from_slice(val);
+        self.ends.push(end);
+        Ok(key)
+    }
+}
+
+impl<T, K, O> Insert<K, Vec<T>> for SliceArena<T, K, O>
+where
+    K: ContiguousIx,
+    O: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_insert(&mut self, mut val: Vec<T>) -> Result<K, Vec<T>> {
+        let Some((key, end)) = self.next_entry(self.data.len() + val.len()) else {
+            return Err(val);
+        };
+        self.data.append(&mut val);
+        self.ends.push(end);
+        Ok(key)
+    }
+}
+
+impl<T, K, O> InsertEmpty<K> for SliceArena<T, K, O>
+where
+    K: ContiguousIx,
+    O: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_insert_empty(&mut self) -> Result<K, ()> {
+        self.try_extend(std::iter::empty()).ok_or(())
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_unique_empty(&mut self) -> Result<K, ()> {
+        self.try_insert_empty()
+    }
+}
+
+impl<T, K, O> Pool<K> for SliceArena<T, K, O>
+where
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn delete(&mut self, _key: K) {}
+}
+
+impl<T, K, O> SafeFreePool<K> for SliceArena<T, K, O> where K: ContiguousIx {}
+impl<T, K, O> DoubleFreePool<K> for SliceArena<T, K, O> where K: ContiguousIx {}
+
+impl<T, K, O> GetRef<K, [T]> for SliceArena<T, K, O>
+where
+    K: ContiguousIx,
+    O: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at(&self, key: K) -> Option<&[T]> {
+        self.get(key)
+    }
+}
+
+impl<T, K, O> GetMut<K, [T]> for SliceArena<T, K, O>
+where
+    K: ContiguousIx,
+    O: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at_mut(&mut self, key: K) -> Option<&mut [T]> {
+        self.get_mut(key)
+    }
+}
+
+/// An arena allocator for strings, packed contiguously into a single buffer
+///
+/// As for a [`SliceArena`], the only per-string overhead is a single end offset of type `O`.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Default)]
+pub struct StrArena<K = usize, O = usize>(SliceArena<u8, K, O>);
+
+impl<K, O> StrArena<K, O> {
+    /// Create a new, empty arena
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new() -> StrArena<K, O> {
+        StrArena(SliceArena::new())
+    }
+
+    /// Create a new, empty arena with space for `strs` strings containing a total of `bytes` bytes
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn with_capacity(strs: usize, bytes: usize) -> StrArena<K, O> {
+        StrArena(SliceArena::with_capacity(strs, bytes))
+    }
+
+    /// Get the number of strings in this arena
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Check whether this arena contains no strings
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Get the total length in bytes of all strings in this arena
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn total_bytes(&self) -> usize {
+        self.0.total_elems()
+    }
+
+    /// Get every string in this arena, concatenated in order of insertion
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn data(&self) -> &str {
+        // SAFETY: the buffer is a concatenation of valid UTF-8 strings
+        unsafe { std::str::from_utf8_unchecked(self.0.data()) }
+    }
+
+    /// Get the underlying arena of bytes
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn as_bytes(&self) -> &SliceArena<u8, K, O> {
+        &self.0
+    }
+}
+
+impl<K, O> StrArena<K, O>
+where
+    K: ContiguousIx,
+    O: ContiguousIx,
+{
+    /// Get the string with a given key, or `None` if the key is invalid
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get(&self, key: K) -> Option<&str> {
+        let bytes = self.0.get(key)?;
+        // SAFETY: every slice in the buffer was inserted as a valid UTF-8 string
+        Some(unsafe { std::str::from_utf8_unchecked(bytes) })
+    }
+
+    /// Get the string with a given key mutably, or `None` if the key is invalid
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn get_mut(&mut self, key: K) -> Option<&mut str> {
+        let bytes = self.0.get_mut(key)?;
+        // SAFETY: every slice in the buffer was inserted as a valid UTF-8 string, and `str` only allows UTF-8 preserving mutation
+        Some(unsafe { std::str::from_utf8_unchecked_mut(bytes) })
+    }
+}
+
+impl<'a, K, O> Insert<K, &'a str> for StrArena<K, O>
+where
+    K: ContiguousIx,
+    O: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_insert(&mut self, val: &'a str) -> Result<K, &'a str> {
+        self.0.try_insert(val.as_bytes()).map_err(|_| val)
+    }
+}
+
+impl<K, O> Insert<K, String> for StrArena<K, O>
+where
+    K: ContiguousIx,
+    O: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_insert(&mut self, val: String) -> Result<K, String> {
+        match self.0.try_insert(val.as_bytes()) {
+            Ok(key) => Ok(key),
+            Err(_) => Err(val),
+        }
+    }
+}
+
+impl<K, O> InsertEmpty<K> for StrArena<K, O>
+where
+    K: ContiguousIx,
+    O: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_insert_empty(&mut self) -> Result<K, ()> {
+        self.0.try_insert_empty()
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_unique_empty(&mut self) -> Result<K, ()> {
+        self.0.insert_unique_empty()
+    }
+}
+
+impl<K, O> Pool<K> for StrArena<K, O>
+where
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn delete(&mut self, _key: K) {}
+}
+
+impl<K, O> SafeFreePool<K> for StrArena<K, O> where K: ContiguousIx {}
+impl<K, O> DoubleFreePool<K> for StrArena<K, O> where K: ContiguousIx {}
+
+impl<K, O> GetRef<K, str> for StrArena<K, O>
+where
+    K: ContiguousIx,
+    O: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at(&self, key: K) -> Option<&str> {
+        self.get(key)
+    }
+}
+
+impl<K, O> GetMut<K, str> for StrArena<K, O>
+where
+    K: ContiguousIx,
+    O: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at_mut(&mut self, key: K) -> Option<&mut str> {
+        self.get_mut(key)
+    }
+}
+
+// The container traits are implemented separately for each key type, since implementing them for a generic key would overlap with their blanket
+// implementations over `ObjectPool`.
+macro_rules! slice_arena_container_pool {
+    ($($ty:ty),*) => {$(
+        impl<T, O> ContainerPool<$ty> for SliceArena<T, $ty, O>
+        where
+            O: ContiguousIx,
+        {
+            type Elem = T;
+        }
+
+        impl<T, O> IsEmptyPool<$ty> for SliceArena<T, $ty, O>
+        where
+            O: ContiguousIx,
+        {
+            #[cfg_attr(not(tarpaulin), inline(always))]
+            fn key_is_empty(&self, key: $ty) -> bool {
+                self.at(key).is_empty()
+            }
+        }
+
+        impl<T, O> LenPool<$ty> for SliceArena<T, $ty, O>
+        where
+            O: ContiguousIx,
+        {
+            #[cfg_attr(not(tarpaulin), inline(always))]
+            fn key_len(&self, key: $ty) -> usize {
+                self.at(key).len()
+            }
+        }
+
+        impl<T, O> GetIndex<$ty, usize, T> for SliceArena<T, $ty, O>
+        where
+            O: ContiguousIx,
+        {
+            #[cfg_attr(not(tarpaulin), inline(always))]
+            fn get_index(&self, key: $ty, elem: usize) -> Option<&T> {
+                self.get(key)?.get(elem)
+            }
+        }
+
+        impl<T, O> GetIndexMut<$ty, usize, T> for SliceArena<T, $ty, O>
+        where
+            O: ContiguousIx,
+        {
+            #[cfg_attr(not(tarpaulin), inline(always))]
+            fn get_index_mut(&mut self, key: $ty, elem: usize) -> Option<&mut T> {
+                self.get_mut(key)?.get_mut(elem)
+            }
+        }
+
+        impl<T, O> SliceRefPool<$ty> for SliceArena<T, $ty, O>
+        where
+            O: ContiguousIx,
+        {
+            #[cfg_attr(not(tarpaulin), inline(always))]
+            fn slice_at(&self, key: $ty) -> &[T] {
+                self.at(key)
+            }
+        }
+
+        impl<T, O> SliceMutPool<$ty> for SliceArena<T, $ty, O>
+        where
+            O: ContiguousIx,
+        {
+            #[cfg_attr(not(tarpaulin), inline(always))]
+            fn slice_at_mut(&mut self, key: $ty) -> &mut [T] {
+                self.at_mut(key)
+            }
+        }
+
+        impl<T, O> IterPool<$ty> for SliceArena<T, $ty, O>
+        where
+            O: ContiguousIx,
+        {
+            type Iter<'a>
+                = std::slice::Iter<'a, T>
+            where
+                Self: 'a,
+                T: 'a;
+
+            #[cfg_attr(not(tarpaulin), inline(always))]
+            fn iter_at(&self, key: $ty) -> Self::Iter<'_> {
+                self.at(key).iter()
+            }
+        }
+
+        impl<T, O> IterMutPool<$ty> for SliceArena<T, $ty, O>
+        where
+            O: ContiguousIx,
+        {
+            type IterMut<'a>
+                = std::slice::IterMut<'a, T>
+            where
+                Self: 'a,
+                T: 'a;
+
+            #[cfg_attr(not(tarpaulin), inline(always))]
+            fn iter_at_mut(&mut self, key: $ty) -> Self::IterMut<'_> {
+                self.at_mut(key).iter_mut()
+            }
+        }
+
+        impl<'a, T, O> InsertFromSlice<'a, $ty> for SliceArena<T, $ty, O>
+        where
+            T: Clone,
+            O: ContiguousIx,
+        {
+            #[cfg_attr(not(tarpaulin), inline(always))]
+            fn insert_from_slice(&mut self, slice: &'a [T]) -> $ty {
+                self.insert(slice)
+            }
+        }
+
+        // Mutable access to the bytes of a string could break UTF-8, so only the read-only traits are implemented
+        impl<O> ContainerPool<$ty> for StrArena<$ty, O>
+        where
+            O: ContiguousIx,
+        {
+            type Elem = u8;
+        }
+
+        impl<O> IsEmptyPool<$ty> for StrArena<$ty, O>
+        where
+            O: ContiguousIx,
+        {
+            #[cfg_attr(not(tarpaulin), inline(always))]
+            fn key_is_empty(&self, key: $ty) -> bool {
+                self.0.key_is_empty(key)
+            }
+        }
+
+        impl<O> LenPool<$ty> for StrArena<$ty, O>
+        where
+            O: ContiguousIx,
+        {
+            #[cfg_attr(not(tarpaulin), inline(always))]
+            fn key_len(&self, key: $ty) -> usize {
+                self.0.key_len(key)
+            }
+        }
+
+        impl<O> GetIndex<$ty, usize, u8> for StrArena<$ty, O>
+        where
+            O: ContiguousIx,
+        {
+            #[cfg_attr(not(tarpaulin), inline(always))]
+            fn get_index(&self, key: $ty, elem: usize) -> Option<&u8> {
+                self.0.get_index(key, elem)
+            }
+        }
+
+        impl<O> SliceRefPool<$ty> for StrArena<$ty, O>
+        where
+            O: ContiguousIx,
+        {
+            #[cfg_attr(not(tarpaulin), inline(always))]
+            fn slice_at(&self, key: $ty) -> &[u8] {
+                self.0.slice_at(key)
+            }
+        }
+
+        impl<O> IterPool<$ty> for StrArena<$ty, O>
+        where
+            O: ContiguousIx,
+        {
+            type Iter<'a>
+                = std::slice::Iter<'a, u8>
+            where
+                Self: 'a;
+
+            #[cfg_attr(not(tarpaulin), inline(always))]
+            fn iter_at(&self, key: $ty) -> Self::Iter<'_> {
+                self.0.iter_at(key)
+            }
+        }
+
+        impl<'a, O> InsertFromSlice<'a, $ty> for StrArena<$ty, O>
+        where
+            O: ContiguousIx,
+        {
+            /// Insert a string from its UTF-8 bytes, panicking if they are not valid UTF-8
+            #[cfg_attr(not(tarpaulin), inline)]
+            #[track_caller]
+            fn insert_from_slice(&mut self, slice: &'a [u8]) -> $ty {
+                let Ok(s) = std::str::from_utf8(slice) else {
+                    panic!("slice inserted into a StrArena is not valid UTF-8")
+                };
+                self.insert(s)
+            }
+        }
+    )*};
+}
+
+slice_arena_container_pool!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+slice_arena_container_pool!(Neg<i8>, Neg<i16>, Neg<i32>, Neg<i64>, Neg<i128>, Neg<isize>);
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pool::container::array::ArrayRefPool;
+
+    fn first_elem<K, P: ArrayRefPool<K>>(pool: &P, key: K) -> Option<&P::Elem> {
+        pool.get_index(key, 0)
+    }
+
+    #[test]
+    fn basic_slice_arena_usage() {
+        let mut arena: SliceArena<u32, u8, u16> = SliceArena::new();
+        assert!(arena.is_empty());
+        let a: u8 = arena.insert(&[1, 2, 3][..]);
+        let e: u8 = arena.insert_empty();
+        let b: u8 = arena.insert(vec![4, 5]);
+        let c: u8 = arena.insert_from_slice(&[6]);
+        assert_eq!(arena.len(), 4);
+        assert_eq!(arena.total_elems(), 6);
+        assert_eq!(arena.data(), [1, 2, 3, 4, 5, 6]);
+        assert_eq!(arena.at(a), [1, 2, 3]);
+        assert_eq!(arena.at(e), []);
+        assert_eq!(arena.slice_at(b), [4, 5]);
+        assert_eq!(arena.try_at(c), Some(&[6][..]));
+        assert_eq!(arena.try_at(4), None);
+        assert!(arena.key_is_empty(e));
+        assert_eq!(arena.key_len(a), 3);
+        assert_eq!(first_elem(&arena, b), Some(&4));
+        assert_eq!(first_elem(&arena, e), None);
+
+        arena.slice_at_mut(a)[1] = 7;
+        *arena.get_index_mut(b, 1).unwrap() = 8;
+        arena.at_mut(c)[0] = 9;
+        arena.delete(a);
+        assert_eq!(arena.data(), [1, 7, 3, 4, 8, 9]);
+    }
+
+    #[test]
+    fn slice_arena_full() {
+        let mut arena: SliceArena<u8, u32, u8> = SliceArena::new();
+        let a: u32 = arena.insert(vec![0; 200]);
+        assert_eq!(arena.try_insert(vec![1; 100]), Err(vec![1; 100]));
+        assert_eq!(arena.try_insert(&[1; 100][..]), Err(&[1; 100][..]));
+        let b: u32 = arena.insert(vec![2; 55]);
+        assert_eq!(arena.total_elems(), 255);
+        assert_eq!(arena.key_len(a), 200);
+        assert_eq!(arena.key_len(b), 55);
+        assert_eq!(arena.try_insert_empty(), Ok(2));
+
+        let mut arena: SliceArena<u8, u8, u32> = SliceArena::new();
+        for _ in 0..256 {
+            let _: u8 = arena.insert_empty();
+        }
+        assert_eq!(arena.try_insert_empty(), Err(()));
+    }
+
+    #[test]
+    fn basic_str_arena_usage() {
+        let mut arena: StrArena<u16, u32> = StrArena::with_capacity(3, 16);
+        let hello: u16 = arena.insert("hello");
+        let empty: u16 = arena.insert_empty();
+        let world: u16 = arena.insert("wörld".to_string());
+        assert_eq!(arena.len(), 3);
+        assert_eq!(arena.total_bytes(), 11);
+        assert_eq!(arena.data(), "hellowörld");
+        assert_eq!(arena.at(hello), "hello");
+        assert_eq!(arena.at(empty), "");
+        assert_eq!(arena.try_at(world), Some("wörld"));
+        assert_eq!(arena.try_at(3), None);
+        arena.at_mut(hello).make_ascii_uppercase();
+        assert_eq!(arena.get(hello), Some("HELLO"));
+
+        let bytes = arena.as_bytes();
+        assert_eq!(bytes.slice_at(hello), b"HELLO");
+        assert_eq!(bytes.key_len(world), 6);
+        assert!(bytes.key_is_empty(empty));
+        assert_eq!(first_elem(bytes, world), Some(&b'w'));
+        assert_eq!(arena.slice_at(world), "wörld".as_bytes());
+        assert_eq!(arena.key_len(hello), 5);
+        assert!(arena.key_is_empty(empty));
+        assert_eq!(first_elem(&arena, hello), Some(&b'H'));
+        let again: u16 = arena.insert_from_slice(b"again");
+        assert_eq!(arena.at(again), "again");
+    }
+}
@@ -0,0 +1,300 @@
+/*!
+An arena allocator whose values never move, supporting insertion through a shared reference
+*/
+use std::{
+    cell::RefCell,
+    marker::PhantomData,
+    ops::{Index, IndexMut},
+};
+
+use crate::index::ContiguousIx;
+
+use super::*;
+
+/// An arena allocator for values of type `V`, stored in chunks of `N` values each which are never moved or reallocated
+///
+/// Unlike an [`Arena`], values may be inserted through a shared reference with [`StableArena::insert`], and so references obtained via
+/// [`StableArena::get`] remain valid while further values are inserted. Mutable access is still available through [`StableArena::get_mut`].
+///
+/// Like an [`Arena`] with [`ByClone`], deletion is a no-op, and values are only dropped when the arena is.
+#[derive(Debug)]
+pub struct StableArena<V, K = usize, const N: usize = 64> {
+    chunks: RefCell<Vec<Vec<V>>>,
+    key_type: PhantomData<K>,
+}
+
+impl<V, K, const N: usize> Clone for StableArena<V, K, N>
+where
+    V: Clone,
+{
+    fn clone(&self) -> Self {
+        // Chunks must be rebuilt with a capacity of `N`, since cloning a `Vec` only reserves its length
+        let chunks = self
+            .chunks
+            .borrow()
+            .iter()
+            .map(|chunk| {
+                let mut new_chunk = Vec::with_capacity(N);
+                new_chunk.extend_from_slice(chunk);
+                new_chunk
+            })
+            .collect();
+        StableArena {
+            chunks: RefCell::new(chunks),
+            key_type: PhantomData,
+        }
+    }
+}
+
+impl<V, K, const N: usize> Default for StableArena<V, K, N> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V, K, const N: usize> StableArena<V, K, N> {
+    /// Create a new, empty arena
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new() -> StableArena<V, K, N> {
+        assert!(N > 0, "chunks must contain at least one value");
+        StableArena {
+            chunks: RefCell::new(Vec::new()),
+            key_type: PhantomData,
+        }
+    }
+
+    /// Get the number of values in this arena
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn len(&self) -> usize {
+        Self::chunks_len(&self.chunks.borrow())
+    }
+
+    /// Check whether this arena is empty
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn is_empty(&self) -> bool {
+        self.chunks.borrow().is_empty()
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn chunks_len(chunks: &[Vec<V>]) -> usize {
+        chunks
+            .last()
+            .map_or(0, |last| (chunks.len() - 1) * N + last.len())
+    }
+
+    /// Insert a value into this arena, returning its key
+    ///
+    /// Panics if the arena is out of keys
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn insert(&self, value: V) -> K
+    where
+        K: ContiguousIx,
+    {
+        match self.try_insert(value) {
+            Ok(key) => key,
+            Err(_) => panic!("arena is out of keys"),
+        }
+    }
+
+    /// Insert a value into this arena, returning its key
+    ///
+    /// Returns the value back if the arena is out of keys
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn try_insert(&self, value: V) -> Result<K, V>
+    where
+        K: ContiguousIx,
+    {
+        let mut chunks = self.chunks.borrow_mut();
+        let Some(key) = K::try_new(Self::chunks_len(&chunks)) else {
+            return Err(value);
+        };
+        match chunks.last_mut() {
+            Some(last) if last.len() < N => last.push(value),
+            _ => {
+                let mut chunk = Vec::with_capacity(N);
+                chunk.push(value);
+                chunks.push(chunk);
+            }
+        }
+        Ok(key)
+    }
+
+    /// Get a reference to the value associated with a given key, or `None` if the key is invalid
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn get(&self, key: K) -> Option<&V>
+    where
+        K: ContiguousIx,
+    {
+        let ix = key.index();
+        let value: *const V = self.chunks.borrow().get(ix / N)?.get(ix % N)?;
+        // SAFETY: a value is never moved or dropped while the arena is only accessible by shared reference, since:
+        // - Chunks, including those of a clone, are allocated with a capacity of at least `N` and never grown beyond `N` values, so they are never reallocated
+        // - Pushing to the list of chunks moves the chunks themselves but not their buffers
+        // - Values are only ever removed, and chunks freed, through a mutable reference to the arena, which cannot coexist with the returned reference
+        Some(unsafe { &*value })
+    }
+
+    /// Get a mutable reference to the value associated with a given key, or `None` if the key is invalid
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn get_mut(&mut self, key: K) -> Option<&mut V>
+    where
+        K: ContiguousIx,
+    {
+        let ix = key.index();
+        self.chunks.get_mut().get_mut(ix / N)?.get_mut(ix % N)
+    }
+
+    /// Convert this arena into a vector of its values, in order of insertion
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn into_vec(self) -> Vec<V> {
+        let chunks = self.chunks.into_inner();
+        let mut result = Vec::with_capacity(Self::chunks_len(&chunks));
+        for chunk in chunks {
+            result.extend(chunk)
+        }
+        result
+    }
+}
+
+impl<V, K, const N: usize> Index<K> for StableArena<V, K, N>
+where
+    K: ContiguousIx,
+{
+    type Output = V;
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn index(&self, index: K) -> &V {
+        self.get(index).expect("key to be valid")
+    }
+}
+
+impl<V, K, const N: usize> IndexMut<K> for StableArena<V, K, N>
+where
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn index_mut(&mut self, index: K) -> &mut V {
+        self.get_mut(index).expect("key to be valid")
+    }
+}
+
+impl<V, K, const N: usize> Insert<K, V> for StableArena<V, K, N>
+where
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_insert(&mut self, val: V) -> Result<K, V> {
+        StableArena::try_insert(self, val)
+    }
+}
+
+impl<V, K, const N: usize> Pool<K> for StableArena<V, K, N>
+where
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn delete(&mut self, _key: K) {}
+}
+
+impl<V, K, const N: usize> ObjectPool<K> for StableArena<V, K, N>
+where
+    K: ContiguousIx,
+{
+    type Object = V;
+}
+
+impl<V, K, const N: usize> SafeFreePool<K> for StableArena<V, K, N> where K: ContiguousIx {}
+impl<V, K, const N: usize> DoubleFreePool<K> for StableArena<V, K, N> where K: ContiguousIx {}
+
+impl<V, K, const N: usize> Take<K, V> for StableArena<V, K, N>
+where
+    K: ContiguousIx,
+    V: Clone,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_take(&mut self, key: K) -> Option<V> {
+        self.get(key).cloned()
+    }
+}
+
+impl<V, K, const N: usize> GetRef<K, V> for StableArena<V, K, N>
+where
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at(&self, key: K) -> Option<&V> {
+        self.get(key)
+    }
+}
+
+impl<V, K, const N: usize> GetMut<K, V> for StableArena<V, K, N>
+where
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at_mut(&mut self, key: K) -> Option<&mut V> {
+        self.get_mut(key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn stable_arena_insert_while_borrowed() {
+        let arena: StableArena<String, u32, 4> = StableArena::new();
+        assert!(arena.is_empty());
+        let first = arena.insert("first".to_string());
+        let first_ref = arena.get(first).unwrap();
+        let keys: Vec<u32> = (0..100).map(|i| arena.insert(i.to_string())).collect();
+        assert_eq!(first_ref, "first");
+        assert_eq!(arena.len(), 101);
+        for (i, key) in keys.iter().enumerate() {
+            assert_eq!(arena[*key], i.to_string());
+        }
+        assert_eq!(arena.get(101), None);
+
+        let mut arena = arena;
+        arena.get_mut(first).unwrap().push('!');
+        arena[keys[0]].push('?');
+        assert_eq!(arena.at(first), "first!");
+        assert_eq!(arena.remove(keys[0]), "0?");
+        arena.delete(keys[0]);
+        assert_eq!(arena.try_at(keys[0]).map(|s| s.as_str()), Some("0?"));
+
+        let values = arena.into_vec();
+        assert_eq!(values.len(), 101);
+        assert_eq!(values[0], "first!");
+        assert_eq!(values[100], "99");
+    }
+
+    #[test]
+    fn stable_arena_clone_insert_while_borrowed() {
+        let arena: StableArena<String, u32, 4> = StableArena::new();
+        for i in 0..6 {
+            arena.insert(i.to_string());
+        }
+        let clone = arena.clone();
+        let last = clone.get(5).unwrap();
+        let last_ptr: *const String = last;
+        for i in 6..16 {
+            clone.insert(i.to_string());
+        }
+        assert_eq!(clone.get(5).map(|s| s as *const String), Some(last_ptr));
+        assert_eq!(last, "5");
+        assert_eq!(clone.len(), 16);
+        assert_eq!(arena.len(), 6);
+    }
+
+    #[test]
+    fn stable_arena_full() {
+        let mut arena: StableArena<u8, u8> = StableArena::default();
+        for i in 0..=255 {
+            assert_eq!(Insert::insert(&mut arena, i), i);
+        }
+        assert_eq!(arena.try_insert(0), Err(0));
+        assert_eq!(arena.len(), 256);
+    }
+}
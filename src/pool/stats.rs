@@ -0,0 +1,333 @@
+/*!
+An instrumentation wrapper around a pool which collects allocation statistics
+*/
+use crate::index::ContiguousIx;
+
+use super::*;
+
+/// A snapshot of the statistics collected by a [`StatsPool`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Default)]
+pub struct PoolStats {
+    /// The number of successful insertions
+    pub inserts: u64,
+    /// The number of insertions which failed because the pool was out of space
+    pub failed_inserts: u64,
+    /// The number of live keys removed with `take` or `try_take`
+    pub removes: u64,
+    /// The number of live keys removed with `delete`
+    pub deletes: u64,
+    /// The number of calls to `delete`, `take` or `try_take` with a key which was not live, e.g. a double free
+    pub stale_frees: u64,
+    /// The number of insertions which reused a previously free'd slot
+    pub free_list_hits: u64,
+    /// The number of insertions which appended a new slot to the pool
+    pub appends: u64,
+    /// The number of live entries
+    pub live: usize,
+    /// The maximum number of live entries seen
+    pub peak_live: usize,
+    /// The maximum number of slots seen, i.e. one more than the largest key index ever returned
+    pub peak_total_slots: usize,
+}
+
+impl PoolStats {
+    /// The number of slots which are currently free, assuming the pool has not been shrunk
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn free_slots(&self) -> usize {
+        self.peak_total_slots - self.live
+    }
+
+    /// The fraction of slots which are currently free, between `0.0` and `1.0`
+    ///
+    /// Returns `0.0` for a pool which has never had any slots
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn fragmentation(&self) -> f64 {
+        if self.peak_total_slots == 0 {
+            0.0
+        } else {
+            self.free_slots() as f64 / self.peak_total_slots as f64
+        }
+    }
+
+    /// The fraction of insertions which reused a free'd slot, between `0.0` and `1.0`
+    ///
+    /// Returns `0.0` if nothing has been inserted
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn free_list_hit_rate(&self) -> f64 {
+        if self.inserts == 0 {
+            0.0
+        } else {
+            self.free_list_hits as f64 / self.inserts as f64
+        }
+    }
+}
+
+/// A wrapper around a pool `P` which counts allocations, removals and slot reuse
+///
+/// Whether an insertion reused a free'd slot is determined by comparing the returned key against the largest key seen so far, so
+/// the statistics are only meaningful for pools which allocate keys contiguously from zero, such as a [`SlabPool`](super::slab::SlabPool),
+/// and which are not cleared or shrunk while wrapped.
+///
+/// Which keys are live is tracked by the wrapper itself, so freeing a key which is not live is counted in [`PoolStats::stale_frees`]
+/// rather than as a removal. A key is considered free once it has been deleted or taken, even if the underlying pool never reuses its
+/// slot, as for an [`Arena`] with [`ByClone`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct StatsPool<P> {
+    pool: P,
+    stats: PoolStats,
+    live_keys: Vec<bool>,
+}
+
+impl<P> StatsPool<P> {
+    /// Wrap a pool, which should not yet have handed out any keys
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new(pool: P) -> StatsPool<P> {
+        StatsPool {
+            pool,
+            stats: PoolStats::default(),
+            live_keys: Vec::new(),
+        }
+    }
+
+    /// Get a reference to the underlying pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn inner(&self) -> &P {
+        &self.pool
+    }
+
+    /// Unwrap this pool, returning the underlying pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn into_inner(self) -> P {
+        self.pool
+    }
+
+    /// Get a snapshot of the statistics collected so far
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn stats(&self) -> PoolStats {
+        self.stats
+    }
+
+    /// Reset all counters to zero, and all peaks to their current values
+    #[cfg_attr(not(tarpaulin), inline)]
+    pub fn reset_stats(&mut self) {
+        self.stats = PoolStats {
+            live: self.stats.live,
+            peak_live: self.stats.live,
+            peak_total_slots: self.stats.peak_total_slots,
+            ..PoolStats::default()
+        }
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn record_insert(&mut self, ix: usize) {
+        let stats = &mut self.stats;
+        stats.inserts += 1;
+        if ix < stats.peak_total_slots {
+            stats.free_list_hits += 1;
+        } else {
+            stats.appends += 1;
+            stats.peak_total_slots = ix + 1;
+        }
+        stats.live += 1;
+        stats.peak_live = stats.peak_live.max(stats.live);
+        if ix >= self.live_keys.len() {
+            self.live_keys.resize(ix + 1, false);
+        }
+        self.live_keys[ix] = true;
+    }
+
+    /// Mark a key as free, returning whether it was live
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn record_free(&mut self, ix: usize) -> bool {
+        match self.live_keys.get_mut(ix) {
+            Some(live) if *live => {
+                *live = false;
+                self.stats.live -= 1;
+                true
+            }
+            _ => {
+                self.stats.stale_frees += 1;
+                false
+            }
+        }
+    }
+}
+
+impl<P, K, V> Insert<K, V> for StatsPool<P>
+where
+    P: Insert<K, V>,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_insert(&mut self, val: V) -> Result<K, V> {
+        match self.pool.try_insert(val) {
+            Ok(key) => {
+                self.record_insert(key.index());
+                Ok(key)
+            }
+            Err(val) => {
+                self.stats.failed_inserts += 1;
+                Err(val)
+            }
+        }
+    }
+}
+
+impl<P, K> Pool<K> for StatsPool<P>
+where
+    P: Pool<K>,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn delete(&mut self, key: K) {
+        let ix = key.index();
+        self.pool.delete(key);
+        if self.record_free(ix) {
+            self.stats.deletes += 1;
+        }
+    }
+}
+
+impl<P, K> ObjectPool<K> for StatsPool<P>
+where
+    P: ObjectPool<K>,
+    K: ContiguousIx,
+{
+    type Object = P::Object;
+}
+
+impl<P, K, V> Take<K, V> for StatsPool<P>
+where
+    P: Take<K, V>,
+    K: ContiguousIx,
+{
+    #[cfg_attr(not(tarpaulin), inline)]
+    fn try_take(&mut self, key: K) -> Option<V> {
+        let ix = key.index();
+        let result = self.pool.try_take(key)?;
+        if self.record_free(ix) {
+            self.stats.removes += 1;
+        }
+        Some(result)
+    }
+}
+
+impl<P, K, V: ?Sized> GetRef<K, V> for StatsPool<P>
+where
+    P: GetRef<K, V>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at(&self, key: K) -> Option<&V> {
+        self.pool.try_at(key)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn at(&self, key: K) -> &V {
+        self.pool.at(key)
+    }
+}
+
+impl<P, K, V: ?Sized> GetMut<K, V> for StatsPool<P>
+where
+    P: GetMut<K, V>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_at_mut(&mut self, key: K) -> Option<&mut V> {
+        self.pool.try_at_mut(key)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn at_mut(&mut self, key: K) -> &mut V {
+        self.pool.at_mut(key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pool::slab::SlabPool;
+    use crate::slot::DefaultSlot;
+
+    #[test]
+    fn basic_stats_pool_usage() {
+        let mut pool: StatsPool<SlabPool<DefaultSlot<u32>, u8>> = StatsPool::new(SlabPool::new());
+        assert_eq!(pool.stats(), PoolStats::default());
+        assert_eq!(pool.stats().fragmentation(), 0.0);
+        assert_eq!(pool.stats().free_list_hit_rate(), 0.0);
+
+        let keys: Vec<u8> = (0..4).map(|i| pool.insert(i)).collect();
+        pool.delete(keys[1]);
+        assert_eq!(pool.remove(keys[2]), 2);
+        assert_eq!(pool.at(keys[3]), &3);
+        *pool.at_mut(keys[3]) = 5;
+        assert_eq!(pool.try_at(keys[3]), Some(&5));
+
+        let stats = pool.stats();
+        assert_eq!(stats.inserts, 4);
+        assert_eq!(stats.appends, 4);
+        assert_eq!(stats.free_list_hits, 0);
+        assert_eq!(stats.deletes, 1);
+        assert_eq!(stats.removes, 1);
+        assert_eq!(stats.live, 2);
+        assert_eq!(stats.peak_live, 4);
+        assert_eq!(stats.peak_total_slots, 4);
+        assert_eq!(stats.free_slots(), 2);
+        assert_eq!(stats.fragmentation(), 0.5);
+        assert_eq!(stats.peak_total_slots, pool.inner().total_slots());
+        assert_eq!(stats.free_slots(), pool.inner().free_slots());
+
+        let _ = pool.insert(7);
+        let _ = pool.insert(8);
+        let _ = pool.insert(9);
+        let stats = pool.stats();
+        assert_eq!(stats.inserts, 7);
+        assert_eq!(stats.free_list_hits, 2);
+        assert_eq!(stats.appends, 5);
+        assert_eq!(stats.peak_live, 5);
+        assert_eq!(stats.peak_total_slots, 5);
+        assert_eq!(stats.fragmentation(), 0.0);
+        assert_eq!(stats.free_list_hit_rate(), 2.0 / 7.0);
+
+        pool.reset_stats();
+        let stats = pool.stats();
+        assert_eq!(stats.inserts, 0);
+        assert_eq!(stats.live, 5);
+        assert_eq!(stats.peak_live, 5);
+        assert_eq!(stats.peak_total_slots, 5);
+
+        for i in 0..251 {
+            let _ = pool.insert(i);
+        }
+        assert_eq!(pool.try_insert(0), Err(0));
+        assert_eq!(pool.stats().failed_inserts, 1);
+        assert_eq!(pool.into_inner().total_slots(), 256);
+    }
+
+    #[test]
+    fn stats_pool_stale_frees() {
+        let mut pool: StatsPool<SlabPool<DefaultSlot<u32>, u8>> = StatsPool::new(SlabPool::new());
+        let a = pool.insert(1);
+        let b = pool.insert(2);
+        pool.delete(a);
+        pool.delete(7);
+        assert_eq!(pool.at(b), &2);
+        let stats = pool.stats();
+        assert_eq!(stats.deletes, 1);
+        assert_eq!(stats.stale_frees, 1);
+        assert_eq!(stats.live, 1);
+        assert_eq!(stats.free_slots(), pool.inner().free_slots());
+
+        let mut arena: StatsPool<Arena<Vec<u32>, u8, ByClone>> =
+            StatsPool::new(Arena::from(Vec::new()));
+        let c = arena.insert(3);
+        assert_eq!(arena.remove(c), 3);
+        assert_eq!(arena.remove(c), 3);
+        arena.delete(c);
+        let stats = arena.stats();
+        assert_eq!(stats.removes, 1);
+        assert_eq!(stats.deletes, 0);
+        assert_eq!(stats.stale_frees, 2);
+        assert_eq!(stats.live, 0);
+        assert_eq!(stats.fragmentation(), 1.0);
+    }
+}
@@ -0,0 +1,345 @@
+/*!
+Pools which check, in debug builds, that keys are only used with the pool which returned them
+*/
+use std::fmt::Debug;
+#[cfg(debug_assertions)]
+use std::sync::atomic::{AtomicU32, Ordering};
+
+use super::{
+    container::{array::InsertFromSlice, ContainerPool, InsertEmpty, InsertWithCapacity},
+    *,
+};
+
+/// The id which will be given to the next [`TaggedPool`] created
+#[cfg(debug_assertions)]
+static NEXT_POOL_ID: AtomicU32 = AtomicU32::new(0);
+
+/// A key of type `K` which, in debug builds, carries the id of the [`TaggedPool`] which returned it
+///
+/// In release builds, this has the same layout as `K`.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tagged<K> {
+    key: K,
+    #[cfg(debug_assertions)]
+    pool: u32,
+}
+
+impl<K> Tagged<K> {
+    /// Get the underlying key, discarding the tag
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn key(self) -> K {
+        self.key
+    }
+}
+
+impl<K> Debug for Tagged<K>
+where
+    K: Debug,
+{
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Tagged").field(&self.key).finish()
+    }
+}
+
+/// A wrapper around a pool `P` whose keys are [`Tagged`] with a unique id in debug builds
+///
+/// In debug builds, using a key returned by a different pool panics; in release builds, no id is stored and nothing is checked.
+/// A clone of a pool is given a new id, so keys must be used with the same copy of the pool which returned them.
+#[derive(Debug)]
+pub struct TaggedPool<P> {
+    pool: P,
+    #[cfg(debug_assertions)]
+    id: u32,
+}
+
+impl<P> Clone for TaggedPool<P>
+where
+    P: Clone,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn clone(&self) -> Self {
+        Self::new(self.pool.clone())
+    }
+}
+
+impl<P> Default for TaggedPool<P>
+where
+    P: Default,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn default() -> Self {
+        Self::new(P::default())
+    }
+}
+
+impl<P> TaggedPool<P> {
+    /// Wrap a pool, giving it a new id
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new(pool: P) -> TaggedPool<P> {
+        TaggedPool {
+            pool,
+            #[cfg(debug_assertions)]
+            id: NEXT_POOL_ID.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    /// Get a reference to the underlying pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn inner(&self) -> &P {
+        &self.pool
+    }
+
+    /// Get a mutable reference to the underlying pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn inner_mut(&mut self) -> &mut P {
+        &mut self.pool
+    }
+
+    /// Unwrap this pool, returning the underlying pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn into_inner(self) -> P {
+        self.pool
+    }
+
+    /// Tag a key of the underlying pool as belonging to this pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn tag<K>(&self, key: K) -> Tagged<K> {
+        Tagged {
+            key,
+            #[cfg(debug_assertions)]
+            pool: self.id,
+        }
+    }
+
+    /// Get the underlying key of a key tagged with this pool
+    ///
+    /// In debug builds, panics if the key was tagged by a different pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    pub fn untag<K>(&self, key: Tagged<K>) -> K {
+        #[cfg(debug_assertions)]
+        if key.pool != self.id {
+            panic!("key from pool {} used with pool {}", key.pool, self.id);
+        }
+        key.key
+    }
+}
+
+impl<P, K, V> Insert<Tagged<K>, V> for TaggedPool<P>
+where
+    P: Insert<K, V>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_insert(&mut self, val: V) -> Result<Tagged<K>, V> {
+        let key = self.pool.try_insert(val)?;
+        Ok(self.tag(key))
+    }
+}
+
+impl<P, K> InsertEmpty<Tagged<K>> for TaggedPool<P>
+where
+    P: InsertEmpty<K>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_insert_empty(&mut self) -> Result<Tagged<K>, ()> {
+        let key = self.pool.try_insert_empty()?;
+        Ok(self.tag(key))
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_unique_empty(&mut self) -> Result<Tagged<K>, ()> {
+        let key = self.pool.insert_unique_empty()?;
+        Ok(self.tag(key))
+    }
+}
+
+impl<P, K, C> InsertWithCapacity<Tagged<K>, C> for TaggedPool<P>
+where
+    P: InsertWithCapacity<K, C>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn try_insert_with_capacity(&mut self, capacity: C) -> Result<Tagged<K>, ()> {
+        let key = self.pool.try_insert_with_capacity(capacity)?;
+        Ok(self.tag(key))
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_unique_with_capacity(&mut self, capacity: C) -> Result<Tagged<K>, ()> {
+        let key = self.pool.insert_unique_with_capacity(capacity)?;
+        Ok(self.tag(key))
+    }
+}
+
+impl<'a, P, K> InsertFromSlice<'a, Tagged<K>> for TaggedPool<P>
+where
+    P: InsertFromSlice<'a, K>,
+    Self: ContainerPool<Tagged<K>, Elem = P::Elem>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn insert_from_slice(&mut self, slice: &'a [P::Elem]) -> Tagged<K> {
+        let key = self.pool.insert_from_slice(slice);
+        self.tag(key)
+    }
+}
+
+impl<P, K> Pool<Tagged<K>> for TaggedPool<P>
+where
+    P: Pool<K>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    fn delete(&mut self, key: Tagged<K>) {
+        let key = self.untag(key);
+        self.pool.delete(key)
+    }
+}
+
+impl<P, K> ObjectPool<Tagged<K>> for TaggedPool<P>
+where
+    P: ObjectPool<K>,
+{
+    type Object = P::Object;
+}
+
+impl<P, K> SafeFreePool<Tagged<K>> for TaggedPool<P> where P: SafeFreePool<K> {}
+impl<P, K> DoubleFreePool<Tagged<K>> for TaggedPool<P> where P: DoubleFreePool<K> {}
+impl<P, K> DoubleRemovePool<Tagged<K>> for TaggedPool<P> where P: DoubleRemovePool<K> {}
+
+impl<P, K, V> Take<Tagged<K>, V> for TaggedPool<P>
+where
+    P: Take<K, V>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    fn try_take(&mut self, key: Tagged<K>) -> Option<V> {
+        let key = self.untag(key);
+        self.pool.try_take(key)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    fn take(&mut self, key: Tagged<K>) -> V {
+        let key = self.untag(key);
+        self.pool.take(key)
+    }
+}
+
+impl<P, K, V: ?Sized> GetRef<Tagged<K>, V> for TaggedPool<P>
+where
+    P: GetRef<K, V>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    fn try_at(&self, key: Tagged<K>) -> Option<&V> {
+        self.pool.try_at(self.untag(key))
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    fn at(&self, key: Tagged<K>) -> &V {
+        self.pool.at(self.untag(key))
+    }
+}
+
+impl<P, K, V: ?Sized> GetMut<Tagged<K>, V> for TaggedPool<P>
+where
+    P: GetMut<K, V>,
+{
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    fn try_at_mut(&mut self, key: Tagged<K>) -> Option<&mut V> {
+        let key = self.untag(key);
+        self.pool.try_at_mut(key)
+    }
+
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    #[track_caller]
+    fn at_mut(&mut self, key: Tagged<K>) -> &mut V {
+        let key = self.untag(key);
+        self.pool.at_mut(key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::{entity::list::EntityList, pool::slab::SlabPool, slot::DefaultSlot};
+
+    type TestPool = TaggedPool<SlabPool<DefaultSlot<u32>, u32>>;
+
+    #[test]
+    fn tagged_pools() {
+        let mut pool = TestPool::new(SlabPool::new());
+        let a = pool.insert(1);
+        let b = pool.insert(2);
+        *pool.at_mut(a) += 10;
+        assert_eq!(pool.at(a), &11);
+        assert_eq!(pool.take(b), 2);
+        pool.delete(a);
+        assert_eq!(pool.inner().free_slots(), 2);
+        assert_eq!(format!("{a:?}"), "Tagged(0)");
+        assert_eq!(pool.untag(pool.tag(5)), 5);
+        let d = pool.insert(7);
+        assert_eq!(pool.clone().inner().at(d.key()), &7);
+        assert_eq!(d.key(), a.key());
+
+        let mut arena = TaggedPool::new(Arena::<Vec<u8>, u8>::from(Vec::new()));
+        let c = arena.insert(3);
+        assert_eq!(arena.take(c), 3);
+        assert_eq!(arena.inner_mut().try_at(0), Some(&0));
+
+        let mut lists: TaggedPool<SlabPool<DefaultSlot<Vec<u32>>, u32>> =
+            TaggedPool::new(SlabPool::new());
+        let mut list = EntityList::new(&mut lists);
+        list.push(4, &mut lists);
+        assert_eq!(list.as_slice(&lists), [4]);
+        let copied = EntityList::from_slice(&[5, 6], &mut lists);
+        assert_eq!(copied.as_slice(&lists), [5, 6]);
+        let reserved = EntityList::<u32, _, _>::new_with_capacity(8, &mut lists);
+        assert!(reserved.is_empty(&lists));
+        assert_eq!(lists.into_inner().total_slots(), 3);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "used with pool")]
+    fn key_from_other_pool() {
+        let mut a = TestPool::new(SlabPool::new());
+        let b = TestPool::new(SlabPool::new());
+        let key = a.insert(0);
+        let _ = b.at(key);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "used with pool")]
+    fn key_from_cloned_pool() {
+        let mut a = TestPool::new(SlabPool::new());
+        let b = a.clone();
+        let key = a.insert(0);
+        let _ = b.at(key);
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "used with pool")]
+    fn entity_list_from_other_pool() {
+        let mut a: TaggedPool<SlabPool<DefaultSlot<Vec<u32>>, u32>> =
+            TaggedPool::new(SlabPool::new());
+        let mut b: TaggedPool<SlabPool<DefaultSlot<Vec<u32>>, u32>> =
+            TaggedPool::new(SlabPool::new());
+        let _ = EntityList::<u32, _, _>::new(&mut b);
+        let mut list = EntityList::new(&mut a);
+        list.push(0, &mut b);
+    }
+
+    #[test]
+    #[cfg(not(debug_assertions))]
+    fn tags_are_erased() {
+        assert_eq!(std::mem::size_of::<Tagged<u32>>(), 4);
+        assert_eq!(
+            std::mem::size_of::<TestPool>(),
+            std::mem::size_of::<SlabPool<DefaultSlot<u32>, u32>>()
+        );
+    }
+}
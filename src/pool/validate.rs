@@ -0,0 +1,381 @@
+/*!
+A debugging wrapper around a pool which checks that keys are used correctly
+*/
+use std::{fmt::Write, panic::Location};
+
+use crate::index::ContiguousIx;
+
+use super::*;
+
+/// The state of a key tracked by a [`ValidatingPool`]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+pub enum KeyState {
+    /// The key is live, having been inserted at `inserted`
+    Live {
+        inserted: &'static Location<'static>,
+    },
+    /// The key has been free'd at `freed`, having been inserted at `inserted`
+    Freed {
+        inserted: &'static Location<'static>,
+        freed: &'static Location<'static>,
+    },
+}
+
+/// A wrapper around a pool `P` which tracks the state of every key it has handed out
+///
+/// Panics with a diagnostic, pointing to where the key was inserted and free'd, on:
+/// - Double free: a `delete`, `take` or `try_take` on a key which has already been free'd
+/// - Stale access: a `try_at`, `at`, `try_at_mut` or `at_mut` on a key which has already been free'd
+/// - Foreign keys: any operation on a key which was never returned by `insert` or `try_insert`
+///
+/// Dropping a `ValidatingPool` while keys are still live panics, listing every leaked key, unless the thread is already panicking.
+/// To discard the pool without checking for leaks, use [`ValidatingPool::into_inner`].
+///
+/// Note that keys which are free'd and then handed out again by the underlying pool are considered live, so a stale key whose slot has
+/// since been reused cannot be detected.
+#[derive(Debug, Clone)]
+pub struct ValidatingPool<P> {
+    pool: P,
+    keys: KeyTracker,
+}
+
+impl<P> ValidatingPool<P> {
+    /// Wrap a pool, which should not yet have handed out any keys
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn new(pool: P) -> ValidatingPool<P> {
+        ValidatingPool {
+            pool,
+            keys: KeyTracker::default(),
+        }
+    }
+
+    /// Get a reference to the underlying pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn inner(&self) -> &P {
+        &self.pool
+    }
+
+    /// Unwrap this pool, returning the underlying pool without checking for leaks
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn into_inner(self) -> P {
+        let ValidatingPool { pool, mut keys } = self;
+        keys.check_leaks = false;
+        pool
+    }
+
+    /// Get the state of a key, or `None` if it was never handed out by this pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn key_state<K: ContiguousIx>(&self, key: K) -> Option<KeyState> {
+        self.keys.states.get(key.index()).copied().flatten()
+    }
+
+    /// Get the number of live keys in this pool
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    pub fn live(&self) -> usize {
+        self.keys.live
+    }
+
+    /// Iterate over the indices of all live keys, along with the location they were inserted at
+    pub fn leaks(&self) -> impl Iterator<Item = (usize, &'static Location<'static>)> + '_ {
+        self.keys.leaks()
+    }
+
+    /// Panic if any keys in this pool are still live
+    #[track_caller]
+    pub fn assert_no_leaks(&self) {
+        if let Some(report) = self.keys.leak_report() {
+            panic!("{report}")
+        }
+    }
+}
+
+impl<P: Default> Default for ValidatingPool<P> {
+    #[cfg_attr(not(tarpaulin), inline(always))]
+    fn default() -> Self {
+        Self::new(P::default())
+    }
+}
+
+/// The per-key state of a [`ValidatingPool`], split out so that it can be forgotten by [`ValidatingPool::into_inner`]
+#[derive(Debug, Clone)]
+struct KeyTracker {
+    states: Vec<Option<KeyState>>,
+    live: usize,
+    check_leaks: bool,
+}
+
+impl Default for KeyTracker {
+    fn default() -> Self {
+        KeyTracker {
+            states: Vec::new(),
+            live: 0,
+            check_leaks: true,
+        }
+    }
+}
+
+impl KeyTracker {
+    #[track_caller]
+    fn inserted(&mut self, ix: usize) {
+        let caller = Location::caller();
+        if ix >= self.states.len() {
+            self.states.resize(ix + 1, None);
+        }
+        if let Some(KeyState::Live { inserted }) = self.states[ix] {
+            panic!(
+                "key {ix} returned by insert at {caller} is already live (inserted at {inserted})"
+            )
+        }
+        self.states[ix] = Some(KeyState::Live { inserted: caller });
+        self.live += 1;
+    }
+
+    /// Check that the key at `ix` is live before performing `op` on it, returning where it was inserted
+    ///
+    /// If `frees` is set, `op` would free the key, so using a free'd key is reported as a double free
+    #[track_caller]
+    fn check_live(&self, ix: usize, op: &str, frees: bool) -> &'static Location<'static> {
+        let caller = Location::caller();
+        match self.states.get(ix).copied().flatten() {
+            Some(KeyState::Live { inserted }) => inserted,
+            Some(KeyState::Freed { inserted, freed }) if frees => panic!(
+                "double free of key {ix} in {op} at {caller}: inserted at {inserted}, already free'd at {freed}"
+            ),
+            Some(KeyState::Freed { inserted, freed }) => panic!(
+                "use of stale key {ix} in {op} at {caller}: inserted at {inserted}, free'd at {freed}"
+            ),
+            None => panic!("use of foreign key {ix} in {op} at {caller}: key was never inserted into this pool"),
+        }
+    }
+
+    #[track_caller]
+    fn freed(&mut self, ix: usize, inserted: &'static Location<'static>) {
+        self.states[ix] = Some(KeyState::Freed {
+            inserted,
+            freed: Location::caller(),
+        });
+        self.live -= 1;
+    }
+
+    fn leaks(&self) -> impl Iterator<Item = (usize, &'static Location<'static>)> + '_ {
+        self.states
+            .iter()
+            .enumerate()
+            .filter_map(|(ix, state)| match state {
+                Some(KeyState::Live { inserted }) => Some((ix, *inserted)),
+                _ => None,
+            })
+    }
+
+    fn leak_report(&self) -> Option<String> {
+        if self.live == 0 {
+            return None;
+        }
+        let mut report = format!("{} leaked key(s):", self.live);
+        for (ix, inserted) in self.leaks() {
+            write!(report, "\n  key {ix} inserted at {inserted}").unwrap();
+        }
+        Some(report)
+    }
+}
+
+impl Drop for KeyTracker {
+    fn drop(&mut self) {
+        if self.check_leaks && !std::thread::panicking() {
+            if let Some(report) = self.leak_report() {
+                panic!("{report}")
+            }
+        }
+    }
+}
+
+impl<P, K, V> Insert<K, V> for ValidatingPool<P>
+where
+    P: Insert<K, V>,
+    K: ContiguousIx,
+{
+    #[track_caller]
+    fn try_insert(&mut self, val: V) -> Result<K, V> {
+        let key = self.pool.try_insert(val)?;
+        self.keys.inserted(key.index());
+        Ok(key)
+    }
+
+    #[track_caller]
+    fn insert(&mut self, val: V) -> K {
+        let key = self.pool.insert(val);
+        self.keys.inserted(key.index());
+        key
+    }
+}
+
+impl<P, K> Pool<K> for ValidatingPool<P>
+where
+    P: Pool<K>,
+    K: ContiguousIx,
+{
+    #[track_caller]
+    fn delete(&mut self, key: K) {
+        let inserted = self.keys.check_live(key.index(), "delete", true);
+        self.pool.delete(key);
+        self.keys.freed(key.index(), inserted);
+    }
+}
+
+impl<P, K> ObjectPool<K> for ValidatingPool<P>
+where
+    P: ObjectPool<K>,
+    K: ContiguousIx,
+{
+    type Object = P::Object;
+}
+
+impl<P, K> SafeFreePool<K> for ValidatingPool<P>
+where
+    P: Pool<K>,
+    K: ContiguousIx,
+{
+}
+
+impl<P, K, V> Take<K, V> for ValidatingPool<P>
+where
+    P: Take<K, V>,
+    K: ContiguousIx,
+{
+    #[track_caller]
+    fn try_take(&mut self, key: K) -> Option<V> {
+        let inserted = self.keys.check_live(key.index(), "take", true);
+        let result = self.pool.try_take(key)?;
+        self.keys.freed(key.index(), inserted);
+        Some(result)
+    }
+
+    #[track_caller]
+    fn take(&mut self, key: K) -> V {
+        let inserted = self.keys.check_live(key.index(), "take", true);
+        let result = self.pool.take(key);
+        self.keys.freed(key.index(), inserted);
+        result
+    }
+}
+
+impl<P, K, V: ?Sized> GetRef<K, V> for ValidatingPool<P>
+where
+    P: GetRef<K, V>,
+    K: ContiguousIx,
+{
+    #[track_caller]
+    fn try_at(&self, key: K) -> Option<&V> {
+        self.keys.check_live(key.index(), "try_at", false);
+        self.pool.try_at(key)
+    }
+
+    #[track_caller]
+    fn at(&self, key: K) -> &V {
+        self.keys.check_live(key.index(), "at", false);
+        self.pool.at(key)
+    }
+}
+
+impl<P, K, V: ?Sized> GetMut<K, V> for ValidatingPool<P>
+where
+    P: GetMut<K, V>,
+    K: ContiguousIx,
+{
+    #[track_caller]
+    fn try_at_mut(&mut self, key: K) -> Option<&mut V> {
+        self.keys.check_live(key.index(), "try_at_mut", false);
+        self.pool.try_at_mut(key)
+    }
+
+    #[track_caller]
+    fn at_mut(&mut self, key: K) -> &mut V {
+        self.keys.check_live(key.index(), "at_mut", false);
+        self.pool.at_mut(key)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::pool::slab::SlabPool;
+    use crate::slot::DefaultSlot;
+
+    type TestPool = ValidatingPool<SlabPool<DefaultSlot<u32>, u32>>;
+
+    #[test]
+    fn basic_validating_pool_usage() {
+        let mut pool = TestPool::new(SlabPool::new());
+        let a = pool.insert(5);
+        let b = pool.insert(6);
+        assert_eq!(pool.live(), 2);
+        assert!(matches!(pool.key_state(a), Some(KeyState::Live { .. })));
+        assert_eq!(pool.key_state(7u32), None);
+        assert_eq!(pool.at(a), &5);
+        *pool.at_mut(b) = 7;
+        assert_eq!(pool.try_at(b), Some(&7));
+        assert_eq!(pool.remove(a), 5);
+        assert!(matches!(pool.key_state(a), Some(KeyState::Freed { .. })));
+        assert_eq!(pool.live(), 1);
+        assert_eq!(pool.leaks().map(|(ix, _)| ix).collect::<Vec<_>>(), [1]);
+        // The free'd slot is reused, and the key is live again
+        assert_eq!(pool.insert(8), a);
+        assert_eq!(pool.at(a), &8);
+        pool.delete(a);
+        pool.delete(b);
+        pool.assert_no_leaks();
+        assert_eq!(pool.inner().total_slots(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "double free of key 0")]
+    fn validating_pool_double_free() {
+        let mut pool = TestPool::new(SlabPool::new());
+        let a = pool.insert(5);
+        pool.delete(a);
+        pool.delete(a);
+    }
+
+    #[test]
+    #[should_panic(expected = "double free of key 0")]
+    fn validating_pool_double_take() {
+        let mut pool = TestPool::new(SlabPool::new());
+        let a = pool.insert(5);
+        pool.delete(a);
+        let _ = pool.try_take(a);
+    }
+
+    #[test]
+    #[should_panic(expected = "use of stale key 0 in at")]
+    fn validating_pool_stale_access() {
+        let mut pool = TestPool::new(SlabPool::new());
+        let a = pool.insert(5);
+        let _b = pool.insert(6);
+        pool.delete(a);
+        let _ = pool.at(a);
+    }
+
+    #[test]
+    #[should_panic(expected = "use of foreign key 3 in try_at_mut")]
+    fn validating_pool_foreign_key() {
+        let mut pool = TestPool::new(SlabPool::new());
+        let _ = pool.try_at_mut(3);
+    }
+
+    #[test]
+    #[should_panic(expected = "1 leaked key(s):\n  key 1 inserted at src/pool/validate.rs")]
+    fn validating_pool_leak() {
+        let mut pool = TestPool::new(SlabPool::new());
+        let a = pool.insert(5);
+        let _b = pool.insert(6);
+        pool.delete(a);
+    }
+
+    #[test]
+    fn validating_pool_into_inner() {
+        let mut pool = TestPool::new(SlabPool::new());
+        let a = pool.insert(5);
+        let pool = pool.into_inner();
+        assert_eq!(pool.at(a), &5);
+    }
+}